@@ -1,51 +1,139 @@
 use std::time::{Duration, Instant};
 
-pub type Result = crate::puzzle::Result<(RuntimeStats, String)>;
-
-#[allow(clippy::type_complexity)]
-pub fn measure<R>(
-    function: impl Fn(&str) -> crate::puzzle::Result<R> + 'static,
-    rounds: u32,
-) -> Box<dyn Fn(&str) -> Result>
-where
-    R: std::fmt::Display,
-{
-    if rounds <= 1 {
-        Box::new(move |input| {
-            let start = Instant::now();
-            let result = function(input)?;
-            let duration = start.elapsed();
-            Ok((duration.into(), result.to_string()))
-        })
-    } else {
-        Box::new(move |input| {
-            let mut accumulator = Vec::with_capacity(rounds as usize);
+use crate::memory::{self, Allocations};
+
+pub type Result = crate::puzzle::Result<(Timings, String)>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sampling {
+    pub warmup: u32,
+    pub rounds: Rounds,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rounds {
+    Fixed(u32),
+    // Samples until the 95% confidence interval of the mean is within `precision` of the mean,
+    // or until `budget` has been spent
+    Adaptive { precision: f64, budget: Duration },
+}
 
-            let mut result = None;
+impl Sampling {
+    const MIN_ADAPTIVE_ROUNDS: usize = 5;
 
-            for _ in 0..rounds {
-                let start = Instant::now();
-                let round_result = function(input)?;
-                let duration = start.elapsed();
-                accumulator.push(duration);
+    pub fn is_single(&self) -> bool {
+        self.rounds == Rounds::Fixed(1)
+    }
 
-                if result.is_none() {
-                    result = Some(round_result);
+    fn is_done(&self, runs: &[Duration], elapsed: Duration) -> bool {
+        match self.rounds {
+            Rounds::Fixed(rounds) => runs.len() >= rounds.max(1) as usize,
+            Rounds::Adaptive { precision, budget } => {
+                if elapsed >= budget {
+                    return !runs.is_empty();
                 }
+
+                if runs.len() < Self::MIN_ADAPTIVE_ROUNDS {
+                    return false;
+                }
+
+                let (mean, stddev) = mean_and_stddev(runs);
+                let half_width = 1.96 * stddev / (runs.len() as f64).sqrt();
+                stddev == 0.0 || half_width <= precision * mean
             }
+        }
+    }
+}
+
+impl Default for Sampling {
+    fn default() -> Self {
+        Self {
+            warmup: 0,
+            rounds: Rounds::Fixed(1),
+        }
+    }
+}
+
+// Runs `function` as often as `sampling` asks for and returns the timings together with the
+// result of the first timed run, and its allocations when counting is enabled
+pub fn sample<R>(
+    sampling: Sampling,
+    mut function: impl FnMut() -> crate::puzzle::Result<R>,
+) -> crate::puzzle::Result<(RuntimeStats, Option<Allocations>, R)> {
+    for _ in 0..sampling.warmup {
+        function()?;
+    }
 
-            Ok((accumulator.into(), result.unwrap().to_string()))
-        })
+    let mut accumulator = Vec::new();
+
+    let mut result = None;
+    let mut allocations = None;
+    let counting = memory::is_enabled();
+
+    let sampling_start = Instant::now();
+    while !sampling.is_done(&accumulator, sampling_start.elapsed()) {
+        let first = result.is_none();
+        if first && counting {
+            memory::reset();
+        }
+
+        let start = Instant::now();
+        let round_result = function()?;
+        let duration = start.elapsed();
+        accumulator.push(duration);
+
+        if first {
+            allocations = counting.then(memory::snapshot);
+            result = Some(round_result);
+        }
     }
+
+    let stats = if sampling.is_single() {
+        accumulator[0].into()
+    } else {
+        accumulator.into()
+    };
+
+    Ok((stats, allocations, result.expect("sampled at least once")))
 }
 
+pub struct Timings {
+    pub parse: RuntimeStats,
+    pub solve: RuntimeStats,
+    pub parse_allocations: Option<Allocations>,
+    pub solve_allocations: Option<Allocations>,
+}
+
+impl std::fmt::Display for Timings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.parse, &self.solve) {
+            (RuntimeStats::Single(_), RuntimeStats::Single(_)) => {
+                write!(f, "parse: {}, solve: {}", self.parse, self.solve)?
+            }
+            _ => write!(f, "parse: [{}], solve: [{}]", self.parse, self.solve)?,
+        }
+
+        if let (Some(parse), Some(solve)) = (self.parse_allocations, self.solve_allocations) {
+            write!(f, " | parse: {parse}, solve: {solve}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
 pub enum RuntimeStats {
     Single([Duration; 1]),
     Multiple {
         runs: Vec<Duration>,
         median: Duration,
+        mean: Duration,
+        stddev: Duration,
         min: Duration,
         max: Duration,
+        p90: Duration,
+        p99: Duration,
+        outliers: usize,
     },
 }
 
@@ -81,12 +169,27 @@ impl From<Vec<Duration>> for RuntimeStats {
         let median = median(sorted.as_slice());
         let min = sorted[0];
         let max = sorted[sorted.len() - 1];
+        let (mean, stddev) = mean_and_stddev(sorted.as_slice());
+
+        // Tukey's fences: runs further than 1.5 interquartile ranges outside the middle half
+        let q1 = percentile(sorted.as_slice(), 25.0);
+        let q3 = percentile(sorted.as_slice(), 75.0);
+        let fence = (q3 - q1).mul_f64(1.5);
+        let outliers = sorted
+            .iter()
+            .filter(|&&run| run + fence < q1 || run > q3 + fence)
+            .count();
 
         Self::Multiple {
-            runs: value,
             median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(stddev),
             min,
             max,
+            p90: percentile(sorted.as_slice(), 90.0),
+            p99: percentile(sorted.as_slice(), 99.0),
+            outliers,
+            runs: value,
         }
     }
 }
@@ -96,25 +199,65 @@ impl std::fmt::Display for RuntimeStats {
         match self {
             Self::Single([duration]) => write!(f, "{}", DurationFormatter(*duration)),
             Self::Multiple {
-                min, max, median, ..
+                runs,
+                median,
+                mean,
+                stddev,
+                min,
+                max,
+                p90,
+                p99,
+                outliers,
             } => {
                 write!(
                     f,
-                    "median: {}, min: {}, max: {}",
+                    "median: {}, mean: {} ± {}, min: {}, max: {}, p90: {}, p99: {}, runs: {}, outliers: {}",
                     DurationFormatter(*median),
+                    DurationFormatter(*mean),
+                    DurationFormatter(*stddev),
                     DurationFormatter(*min),
-                    DurationFormatter(*max)
+                    DurationFormatter(*max),
+                    DurationFormatter(*p90),
+                    DurationFormatter(*p99),
+                    runs.len(),
+                    outliers
                 )
             }
         }
     }
 }
 
+// Mean and sample standard deviation in seconds
+fn mean_and_stddev(runs: &[Duration]) -> (f64, f64) {
+    assert!(!runs.is_empty());
+
+    let n = runs.len() as f64;
+    let mean = runs.iter().map(Duration::as_secs_f64).sum::<f64>() / n;
+    if runs.len() == 1 {
+        return (mean, 0.0);
+    }
+
+    let variance = runs
+        .iter()
+        .map(|run| (run.as_secs_f64() - mean).powi(2))
+        .sum::<f64>()
+        / (n - 1.0);
+    (mean, variance.sqrt())
+}
+
+// Nearest-rank percentile of a sorted slice
+fn percentile(sorted: &[Duration], percent: f64) -> Duration {
+    assert!(!sorted.is_empty());
+
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
 fn median(slice: &[Duration]) -> Duration {
     assert!(!slice.is_empty());
 
     let middle = slice.len() / 2;
-    if slice.len() % 2 == 0 {
+    if slice.len().is_multiple_of(2) {
         (slice[middle - 1] + slice[middle]) / 2
     } else {
         slice[middle]
@@ -123,6 +266,12 @@ fn median(slice: &[Duration]) -> Duration {
 
 pub struct DurationFormatter(pub Duration);
 
+impl DurationFormatter {
+    pub fn from_millis(millis: f64) -> Self {
+        Self(Duration::from_secs_f64(millis / 1000.0))
+    }
+}
+
 impl std::fmt::Display for DurationFormatter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let nanos = self.0.as_nanos();
@@ -144,3 +293,109 @@ impl std::fmt::Display for DurationFormatter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[rstest]
+    fn test_stats() {
+        crate::util::test::setup_tracing();
+
+        let mut runs = millis(&[10, 11, 12, 13, 14, 15, 16, 17, 18, 100]);
+        runs.reverse();
+
+        let RuntimeStats::Multiple {
+            median,
+            mean,
+            min,
+            max,
+            p90,
+            p99,
+            outliers,
+            ..
+        } = RuntimeStats::from(runs)
+        else {
+            panic!("expected multiple runs");
+        };
+
+        assert_eq!(median, Duration::from_micros(14500));
+        assert_eq!(mean.as_micros(), 22600);
+        assert_eq!(
+            (min, max),
+            (Duration::from_millis(10), Duration::from_millis(100))
+        );
+        assert_eq!(p90, Duration::from_millis(18));
+        assert_eq!(p99, Duration::from_millis(100));
+        assert_eq!(outliers, 1);
+    }
+
+    #[rstest]
+    #[case(&[1], 0.0)]
+    #[case(&[5, 1, 3], 2.0)]
+    #[case(&[2, 4, 4, 4, 5, 5, 7, 9], (32.0_f64 / 7.0).sqrt())]
+    fn test_stddev(#[case] runs: &[u64], #[case] expected: f64) {
+        crate::util::test::setup_tracing();
+        let (_, stddev) = mean_and_stddev(&millis(runs));
+        assert!((stddev * 1000.0 - expected).abs() < 1e-9, "{stddev}");
+    }
+
+    #[rstest]
+    #[case(50.0, 5)]
+    #[case(90.0, 9)]
+    #[case(99.0, 10)]
+    #[case(0.0, 1)]
+    fn test_percentile(#[case] percent: f64, #[case] expected: u64) {
+        crate::util::test::setup_tracing();
+        let runs = millis(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        assert_eq!(percentile(&runs, percent), Duration::from_millis(expected));
+    }
+
+    #[rstest]
+    fn test_sampling() -> crate::puzzle::Result<()> {
+        crate::util::test::setup_tracing();
+
+        let mut calls = 0;
+        let fixed = Sampling {
+            warmup: 2,
+            rounds: Rounds::Fixed(3),
+        };
+        let (stats, _, result) = sample(fixed, || {
+            calls += 1;
+            Ok(calls)
+        })?;
+        assert_eq!(stats.runs().len(), 3);
+        assert_eq!((calls, result), (5, 3));
+
+        let adaptive = Sampling {
+            warmup: 0,
+            rounds: Rounds::Adaptive {
+                precision: f64::INFINITY,
+                budget: Duration::from_secs(10),
+            },
+        };
+        let (stats, _, _) = sample(adaptive, || Ok(()))?;
+        assert_eq!(stats.runs().len(), Sampling::MIN_ADAPTIVE_ROUNDS);
+
+        let budgeted = Sampling {
+            warmup: 0,
+            rounds: Rounds::Adaptive {
+                precision: 0.0,
+                budget: Duration::from_millis(20),
+            },
+        };
+        let start = Instant::now();
+        sample(budgeted, || {
+            std::thread::sleep(Duration::from_millis(1));
+            Ok(())
+        })?;
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,404 @@
+use std::{collections::BTreeMap, fmt::Write, path::Path};
+
+use itertools::Itertools;
+
+use aoc2019::{
+    benchmark::DurationFormatter,
+    report::{Entry, Report},
+};
+
+use crate::Result;
+
+const COLORS: [&str; 6] = [
+    "#4e79a7", "#f28e2b", "#59a14f", "#e15759", "#b07aa1", "#76b7b2",
+];
+
+const LABEL_WIDTH: f64 = 130.0;
+const PLOT_WIDTH: f64 = 640.0;
+const ROW_GAP: f64 = 8.0;
+const BAR_HEIGHT: f64 = 12.0;
+const AXIS_HEIGHT: f64 = 24.0;
+
+// One chart row: the shared parse of a puzzle (`part` is `None`) or the solve of one part,
+// for one input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Row<'a> {
+    puzzle: u32,
+    part: Option<u32>,
+    source: &'a str,
+}
+
+impl std::fmt::Display for Row<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.part {
+            Some(part) => write!(f, "Day {:02} part {part}", self.puzzle),
+            None => write!(f, "Day {:02} parse", self.puzzle),
+        }
+    }
+}
+
+// Entries of every row, one column per report
+struct Table<'a> {
+    names: Vec<String>,
+    rows: BTreeMap<Row<'a>, Vec<Option<&'a Entry>>>,
+    // Some puzzle was run on several inputs, so the labels name them
+    sources: bool,
+}
+
+impl<'a> Table<'a> {
+    fn new(reports: &'a [(String, Report)]) -> Self {
+        let mut rows = BTreeMap::new();
+
+        for (column, (_, report)) in reports.iter().enumerate() {
+            // A timeout's only sample is the time limit rather than a run time
+            for entry in report.entries().iter().filter(|entry| !entry.is_timeout()) {
+                let part = (entry.phase != "Parse").then_some(entry.part);
+                let row = Row {
+                    puzzle: entry.puzzle,
+                    part,
+                    source: &entry.source,
+                };
+
+                let columns = rows.entry(row).or_insert_with(|| vec![None; reports.len()]);
+                // Both parts record the same parse, so only the first one is kept
+                columns[column].get_or_insert(entry);
+            }
+        }
+
+        let sources = rows
+            .keys()
+            .tuple_windows()
+            .any(|(a, b)| (a.puzzle, a.part) == (b.puzzle, b.part));
+
+        Self {
+            names: reports.iter().map(|(name, _)| name.clone()).collect(),
+            rows,
+            sources,
+        }
+    }
+
+    fn label(&self, row: &Row) -> String {
+        if !self.sources {
+            return row.to_string();
+        }
+
+        let file = Path::new(row.source)
+            .file_name()
+            .map_or(row.source.into(), |name| name.to_string_lossy());
+        escape(&format!("{row} ({file})"))
+    }
+
+    // Run times span several orders of magnitude, so the axis is logarithmic and covers
+    // whole decades
+    fn scale(&self) -> Scale {
+        let (min, max) = self
+            .rows
+            .values()
+            .flatten()
+            .flatten()
+            .flat_map(|entry| entry.samples.iter())
+            .filter(|&&value| value > 0.0)
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+                (min.min(value), max.max(value))
+            });
+
+        if min > max {
+            return Scale { low: -3, high: 0 };
+        }
+
+        let low = min.log10().floor() as i32;
+        let high = (max.log10().ceil() as i32).max(low + 1);
+        Scale { low, high }
+    }
+}
+
+// Decades of milliseconds from `10^low` to `10^high`
+struct Scale {
+    low: i32,
+    high: i32,
+}
+
+impl Scale {
+    fn x(&self, millis: f64) -> f64 {
+        let value = millis.max(10f64.powi(self.low)).log10();
+        let fraction = (value - self.low as f64) / (self.high - self.low) as f64;
+        LABEL_WIDTH + fraction * PLOT_WIDTH
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    // Median of every report as a bar
+    Bars,
+    // Every run as a dot, with the median marked
+    Runs,
+}
+
+pub fn save(reports: &[(String, Report)], path: impl AsRef<Path>) -> Result<()> {
+    std::fs::write(path, render(reports))?;
+    Ok(())
+}
+
+pub fn render(reports: &[(String, Report)]) -> String {
+    let table = Table::new(reports);
+    let scale = table.scale();
+
+    let mut html = String::new();
+    html.push_str(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Benchmark report</title>\n<style>\n\
+         body { font-family: sans-serif; margin: 2em; }\n\
+         svg text { font-size: 11px; }\n\
+         .legend span { display: inline-block; width: 12px; height: 12px; margin: 0 4px 0 12px; }\n\
+         table { border-collapse: collapse; }\n\
+         td, th { padding: 2px 10px; text-align: right; }\n\
+         td:first-child, th:first-child { text-align: left; }\n\
+         .slower { color: #c0392b; }\n.faster { color: #27ae60; }\n\
+         </style>\n</head>\n<body>\n",
+    );
+
+    html.push_str("<h1>Benchmark report</h1>\n<p class=\"legend\">");
+    for (idx, name) in table.names.iter().enumerate() {
+        let color = COLORS[idx % COLORS.len()];
+        let _ = write!(
+            html,
+            "<span style=\"background: {color}\"></span>{}",
+            escape(name)
+        );
+    }
+    html.push_str("</p>\n");
+
+    html.push_str("<h2>Median times</h2>\n");
+    html.push_str(&svg(&table, &scale, Style::Bars));
+    html.push_str("<h2>Runs</h2>\n");
+    html.push_str(&svg(&table, &scale, Style::Runs));
+
+    if table.names.len() > 1 {
+        html.push_str("<h2>Comparison</h2>\n");
+        html.push_str(&comparison(&table));
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn svg(table: &Table, scale: &Scale, style: Style) -> String {
+    let columns = table.names.len().max(1) as f64;
+    let row_height = columns * BAR_HEIGHT + ROW_GAP;
+    let width = LABEL_WIDTH + PLOT_WIDTH + 20.0;
+    let height = table.rows.len() as f64 * row_height + AXIS_HEIGHT;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">"
+    );
+
+    for decade in scale.low..=scale.high {
+        let x = scale.x(10f64.powi(decade));
+        let label = DurationFormatter::from_millis(10f64.powi(decade));
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{x:.1}\" y1=\"0\" x2=\"{x:.1}\" y2=\"{:.1}\" stroke=\"#ddd\"/>\
+             <text x=\"{x:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{label}</text>",
+            height - AXIS_HEIGHT,
+            height - 8.0,
+        );
+    }
+
+    for (idx, (row, columns)) in table.rows.iter().enumerate() {
+        let top = idx as f64 * row_height;
+        let _ = writeln!(
+            svg,
+            "<text x=\"0\" y=\"{:.1}\">{}</text>",
+            top + (row_height - ROW_GAP) / 2.0 + 4.0,
+            table.label(row)
+        );
+
+        for (column, entry) in columns.iter().enumerate() {
+            let Some(entry) = entry else {
+                continue;
+            };
+
+            let color = COLORS[column % COLORS.len()];
+            let y = top + column as f64 * BAR_HEIGHT;
+            let median = entry.median();
+            let title = format!(
+                "{}: {}",
+                escape(&table.names[column]),
+                DurationFormatter::from_millis(median)
+            );
+
+            match style {
+                Style::Bars => {
+                    let _ = writeln!(
+                        svg,
+                        "<rect class=\"bar\" x=\"{LABEL_WIDTH}\" y=\"{y:.1}\" width=\"{:.1}\" \
+                         height=\"{:.1}\" fill=\"{color}\"><title>{title}</title></rect>",
+                        scale.x(median) - LABEL_WIDTH,
+                        BAR_HEIGHT - 2.0,
+                    );
+                }
+                Style::Runs => {
+                    let cy = y + BAR_HEIGHT / 2.0;
+                    for &value in entry.samples.iter() {
+                        let _ = writeln!(
+                            svg,
+                            "<circle class=\"run\" cx=\"{:.1}\" cy=\"{cy:.1}\" r=\"2.5\" \
+                             fill=\"{color}\" fill-opacity=\"0.4\"/>",
+                            scale.x(value)
+                        );
+                    }
+
+                    let x = scale.x(median);
+                    let _ = writeln!(
+                        svg,
+                        "<line x1=\"{x:.1}\" y1=\"{y:.1}\" x2=\"{x:.1}\" y2=\"{:.1}\" \
+                         stroke=\"{color}\" stroke-width=\"2\"><title>{title}</title></line>",
+                        y + BAR_HEIGHT - 2.0
+                    );
+                }
+            }
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// Medians of every report with the change relative to the first report
+fn comparison(table: &Table) -> String {
+    let mut html = String::from("<table>\n<tr><th></th>");
+    for name in table.names.iter() {
+        let _ = write!(html, "<th>{}</th>", escape(name));
+    }
+    html.push_str("</tr>\n");
+
+    for (row, columns) in table.rows.iter() {
+        let _ = write!(html, "<tr><td>{}</td>", table.label(row));
+        let first = columns[0].map(Entry::median);
+
+        for (column, entry) in columns.iter().enumerate() {
+            let Some(entry) = entry else {
+                html.push_str("<td>-</td>");
+                continue;
+            };
+
+            let median = entry.median();
+            let _ = write!(html, "<td>{}", DurationFormatter::from_millis(median));
+            if let Some(first) = first.filter(|&first| column > 0 && first > 0.0) {
+                let change = (median - first) / first * 100.0;
+                let class = if change > 0.0 { "slower" } else { "faster" };
+                let _ = write!(html, " <span class=\"{class}\">({change:+.1}%)</span>");
+            }
+            html.push_str("</td>");
+        }
+
+        html.push_str("</tr>\n");
+    }
+
+    html.push_str("</table>\n");
+    html
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use std::time::Duration;
+
+    fn report(scale: f64) -> Report {
+        let mut report = Report::default();
+        for (part, phase) in [(1, "Parse"), (1, "Solve"), (2, "Parse"), (2, "Solve")] {
+            report.push(Entry {
+                puzzle: 1,
+                part,
+                source: "inputs/day01/input.txt".to_string(),
+                phase: phase.to_string(),
+                samples: vec![0.01 * scale, 0.02 * scale, 0.03 * scale],
+                allocations: None,
+            });
+        }
+        report
+    }
+
+    #[rstest]
+    fn test_render() {
+        aoc2019::util::test::setup_tracing();
+
+        let reports = [
+            ("before".to_string(), report(1.0)),
+            ("<after>".to_string(), report(2.0)),
+        ];
+        let html = render(&reports);
+
+        // Parse, part 1 and part 2 for each report
+        assert_eq!(html.matches("class=\"bar\"").count(), 6);
+        assert_eq!(html.matches("class=\"run\"").count(), 18);
+        assert!(html.contains("&lt;after&gt;"));
+        assert!(html.contains("(+100.0%)"));
+        assert!(!html.contains("<after>"));
+    }
+
+    #[rstest]
+    fn test_render_timeout() {
+        aoc2019::util::test::setup_tracing();
+
+        let mut timed_out = report(1.0);
+        timed_out.push_timeout(2, 1, "inputs/day02/input.txt", Duration::from_secs(10));
+        let html = render(&[("timeout".to_string(), timed_out)]);
+
+        assert_eq!(html.matches("class=\"bar\"").count(), 3);
+        assert!(!html.contains("Day 02"));
+    }
+
+    #[rstest]
+    fn test_render_sources() {
+        aoc2019::util::test::setup_tracing();
+
+        let mut both = report(1.0);
+        both.push(Entry {
+            puzzle: 1,
+            part: 1,
+            source: "inputs/day01/test.1.txt".to_string(),
+            phase: "Solve".to_string(),
+            samples: vec![0.001],
+            allocations: None,
+        });
+        let html = render(&[("both".to_string(), both)]);
+
+        // Each input gets its own row rather than the first one hiding the other
+        assert_eq!(html.matches("class=\"bar\"").count(), 4);
+        assert!(html.contains("Day 01 part 1 (input.txt)"));
+        assert!(html.contains("Day 01 part 1 (test.1.txt)"));
+    }
+
+    #[rstest]
+    #[case(&[0.002, 0.5], -3, 0)]
+    #[case(&[0.001, 0.001], -3, -2)]
+    #[case(&[12.0, 2500.0], 1, 4)]
+    fn test_scale(#[case] samples: &[f64], #[case] low: i32, #[case] high: i32) {
+        aoc2019::util::test::setup_tracing();
+
+        let mut report = Report::default();
+        report.push(Entry {
+            puzzle: 1,
+            part: 1,
+            source: String::new(),
+            phase: "Solve".to_string(),
+            samples: samples.to_vec(),
+            allocations: None,
+        });
+        let reports = [(String::new(), report)];
+        let scale = Table::new(&reports).scale();
+        assert_eq!((scale.low, scale.high), (low, high));
+    }
+}
@@ -0,0 +1,302 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use aoc2019::{
+    benchmark::DurationFormatter,
+    report::{Entry, Format, Report},
+};
+
+use crate::{Error, Result};
+
+// Relative to the working directory unless the config moves it, and outside `target/` so that
+// `cargo clean` keeps the baselines
+pub const BASELINE_DIR: &str = "baselines";
+
+// Fewer samples than this can't make a difference significant
+pub const MIN_SAMPLES: usize = 5;
+const SIGNIFICANCE: f64 = 0.05;
+
+pub fn baseline_path(directory: &Path, name: &str) -> PathBuf {
+    directory.join(format!("{name}.csv"))
+}
+
+pub fn save_baseline(report: &Report, directory: &Path, name: &str) -> Result<PathBuf> {
+    let path = baseline_path(directory, name);
+    std::fs::create_dir_all(directory)?;
+    report.save_to(&path, Format::Csv)?;
+    Ok(path)
+}
+
+pub fn load_baseline(directory: &Path, name: &str) -> Result<Report> {
+    let path = baseline_path(directory, name);
+    if !path.exists() {
+        return Err(Error::NoSuchBaseline {
+            name: name.to_string(),
+        });
+    }
+
+    Ok(Report::load_from(path)?)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub puzzle: u32,
+    pub part: u32,
+    pub phase: String,
+    // Median run times in milliseconds
+    pub baseline: Option<f64>,
+    pub current: f64,
+    pub p_value: Option<f64>,
+    pub regression: bool,
+    // The current run timed out after `current` milliseconds
+    pub timed_out: bool,
+}
+
+impl Comparison {
+    // Relative change of the median in percent
+    pub fn change(&self) -> Option<f64> {
+        self.baseline
+            .filter(|&baseline| baseline > 0.0)
+            .map(|baseline| (self.current - baseline) / baseline * 100.0)
+    }
+}
+
+impl std::fmt::Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let millis = DurationFormatter::from_millis;
+
+        write!(
+            f,
+            "Day {:02} part {} {}: ",
+            self.puzzle,
+            self.part,
+            self.phase.to_lowercase()
+        )?;
+
+        if self.timed_out {
+            match self.baseline {
+                Some(baseline) => write!(f, "{} -> ", millis(baseline))?,
+                None => write!(f, "new, ")?,
+            }
+            write!(f, "timed out after {}", millis(self.current))?;
+            if self.regression {
+                write!(f, " REGRESSION")?;
+            }
+            return Ok(());
+        }
+
+        let Some(baseline) = self.baseline else {
+            return write!(f, "{} (new)", millis(self.current));
+        };
+
+        write!(f, "{} -> {}", millis(baseline), millis(self.current))?;
+        match (self.change(), self.p_value) {
+            (Some(change), Some(p)) => write!(f, " ({change:+.1}%, p = {p:.3})")?,
+            (Some(change), None) => write!(f, " ({change:+.1}%)")?,
+            _ => {}
+        }
+
+        if self.regression {
+            write!(f, " REGRESSION")?;
+        }
+
+        Ok(())
+    }
+}
+
+// Matches every entry of the current run with the baseline entry for the same puzzle, part,
+// input and phase. A part regresses when its median got slower by more than `threshold`
+// percent and the samples differ significantly, or when it timed out after having finished in
+// the baseline.
+pub fn compare(baseline: &Report, current: &Report, threshold: f64) -> Vec<Comparison> {
+    let baseline = baseline
+        .entries()
+        .iter()
+        .map(|entry| (entry.key(), entry))
+        .collect::<HashMap<_, _>>();
+
+    current
+        .entries()
+        .iter()
+        .map(|entry| {
+            let (puzzle, part, source, phase) = entry.key();
+            let phase = if entry.is_timeout() { "Solve" } else { phase };
+            let previous = baseline.get(&(puzzle, part, source, phase)).copied();
+            compare_entry(previous, entry, threshold)
+        })
+        .collect()
+}
+
+fn compare_entry(baseline: Option<&Entry>, current: &Entry, threshold: f64) -> Comparison {
+    let mut comparison = Comparison {
+        puzzle: current.puzzle,
+        part: current.part,
+        phase: current.phase.clone(),
+        baseline: baseline.map(Entry::median),
+        current: current.median(),
+        p_value: None,
+        regression: false,
+        timed_out: current.is_timeout(),
+    };
+
+    if comparison.timed_out {
+        comparison.phase = "Solve".to_string();
+        comparison.regression = baseline.is_some();
+    } else if let Some(baseline) = baseline {
+        comparison.p_value = mann_whitney(&baseline.samples, &current.samples);
+        comparison.regression = comparison.p_value.is_some_and(|p| p < SIGNIFICANCE)
+            && comparison.change().is_some_and(|change| change > threshold);
+    }
+
+    comparison
+}
+
+// Two-sided p-value of the Mann-Whitney U test, using the normal approximation. Run times are
+// rarely normally distributed, so a rank test is a better fit than comparing means.
+fn mann_whitney(a: &[f64], b: &[f64]) -> Option<f64> {
+    if a.len() < MIN_SAMPLES || b.len() < MIN_SAMPLES {
+        return None;
+    }
+
+    let mut values = a
+        .iter()
+        .map(|&value| (value, true))
+        .chain(b.iter().map(|&value| (value, false)))
+        .collect::<Vec<_>>();
+    values.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    // Tied values share the average of their ranks
+    let mut rank_sum = 0.0;
+    let mut start = 0;
+    while start < values.len() {
+        let end = start
+            + values[start..]
+                .iter()
+                .take_while(|(value, _)| *value == values[start].0)
+                .count();
+        let rank = (start + end + 1) as f64 / 2.0;
+        let count = values[start..end].iter().filter(|(_, in_a)| *in_a).count();
+        rank_sum += rank * count as f64;
+        start = end;
+    }
+
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let u = rank_sum - n1 * (n1 + 1.0) / 2.0;
+    let mean = n1 * n2 / 2.0;
+    let sigma = (n1 * n2 * (n1 + n2 + 1.0) / 12.0).sqrt();
+
+    let z = (u - mean).abs() / sigma;
+    Some(erfc(z / std::f64::consts::SQRT_2).min(1.0))
+}
+
+// Abramowitz and Stegun 7.1.26, accurate to about 1e-7
+fn erfc(x: f64) -> f64 {
+    const P: f64 = 0.3275911;
+    const A: [f64; 5] = [
+        0.254829592,
+        -0.284496736,
+        1.421413741,
+        -1.453152027,
+        1.061405429,
+    ];
+
+    let t = 1.0 / (1.0 + P * x.abs());
+    let polynomial = A.iter().rev().fold(0.0, |acc, a| acc * t + a) * t;
+    let value = polynomial * (-x * x).exp();
+
+    if x >= 0.0 {
+        value
+    } else {
+        2.0 - value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use std::time::Duration;
+
+    fn entry(part: u32, samples: &[f64]) -> Entry {
+        Entry {
+            puzzle: 1,
+            part,
+            source: "inputs/day01/input.txt".to_string(),
+            phase: "Solve".to_string(),
+            samples: samples.to_vec(),
+            allocations: None,
+        }
+    }
+
+    #[rstest]
+    #[case(0.0, 1.0)]
+    #[case(0.5, 0.4795001)]
+    #[case(1.0, 0.1572992)]
+    #[case(-1.0, 1.8427008)]
+    fn test_erfc(#[case] x: f64, #[case] expected: f64) {
+        aoc2019::util::test::setup_tracing();
+        assert!((erfc(x) - expected).abs() < 1e-6, "{}", erfc(x));
+    }
+
+    #[rstest]
+    #[case(&[1.0, 2.0, 3.0, 4.0, 5.0], &[1.0, 2.0, 3.0, 4.0, 5.0], Some(1.0))]
+    #[case(&[1.0, 2.0, 3.0, 4.0, 5.0], &[6.0, 7.0, 8.0, 9.0, 10.0], Some(0.0090))]
+    #[case(&[1.0, 2.0, 3.0, 4.0], &[6.0, 7.0, 8.0, 9.0], None)]
+    fn test_mann_whitney(#[case] a: &[f64], #[case] b: &[f64], #[case] expected: Option<f64>) {
+        aoc2019::util::test::setup_tracing();
+        let result = mann_whitney(a, b);
+        match (result, expected) {
+            (Some(result), Some(expected)) => {
+                assert!((result - expected).abs() < 1e-3, "{result}")
+            }
+            _ => assert_eq!(result, expected),
+        }
+    }
+
+    #[rstest]
+    fn test_compare() {
+        aoc2019::util::test::setup_tracing();
+
+        let mut baseline = Report::default();
+        let mut current = Report::default();
+        let slow = [2.0, 2.1, 2.2, 2.3, 2.4];
+        let fast = [1.0, 1.1, 1.2, 1.3, 1.4];
+        baseline.push(entry(1, &fast));
+        baseline.push(entry(2, &slow));
+        current.push(entry(1, &slow));
+        current.push(entry(2, &fast));
+        current.push(entry(3, &fast));
+
+        let comparisons = compare(&baseline, &current, 5.0);
+        let regressions = comparisons
+            .iter()
+            .map(|comparison| comparison.regression)
+            .collect::<Vec<_>>();
+        assert_eq!(regressions, [true, false, false]);
+
+        let changes = comparisons
+            .iter()
+            .map(Comparison::change)
+            .collect::<Vec<_>>();
+        assert!((changes[0].unwrap() - 83.33).abs() < 0.01);
+        assert!((changes[1].unwrap() + 45.45).abs() < 0.01);
+        assert_eq!(changes[2], None);
+
+        // A significant change below the threshold is not a regression
+        let comparisons = compare(&baseline, &current, 100.0);
+        assert!(comparisons.iter().all(|comparison| !comparison.regression));
+
+        let mut timed_out = Report::default();
+        timed_out.push_timeout(1, 2, "inputs/day01/input.txt", Duration::from_secs(1));
+        timed_out.push_timeout(1, 3, "inputs/day01/input.txt", Duration::from_secs(1));
+        let comparisons = compare(&baseline, &timed_out, 5.0);
+        assert_eq!(comparisons[0].baseline, Some(2.2));
+        assert!(comparisons[0].regression && !comparisons[1].regression);
+        assert_eq!(
+            comparisons[0].to_string(),
+            "Day 01 part 2 solve: 2.2ms -> timed out after 1000.0ms REGRESSION"
+        );
+    }
+}
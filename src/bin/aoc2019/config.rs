@@ -0,0 +1,246 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::Deserialize;
+use tracing_subscriber::EnvFilter;
+
+use aoc2019::report::Format;
+
+use crate::{Error, Result};
+
+// Looked up in the working directory, which is the project root for `cargo run`
+pub const FILE_NAME: &str = "aoc2019.toml";
+
+// Defaults for the runner's options, which command line flags override
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub rounds: Option<u32>,
+    pub warmup: Option<u32>,
+    pub format: Option<Format>,
+    pub report: Option<PathBuf>,
+    pub inputs: Option<PathBuf>,
+    pub baselines: Option<PathBuf>,
+    pub log: Option<String>,
+    pub timeout: Option<Duration>,
+    pub day_timeouts: HashMap<u32, Duration>,
+}
+
+// The file as written, for example
+//
+//     rounds = 10
+//     warmup = 2
+//     inputs = "../aoc-inputs"
+//     baselines = "../aoc-baselines"
+//     log = "aoc2019=debug"
+//
+//     [report]
+//     format = "json"
+//     path = "target/report.json"
+//
+//     [timeouts]
+//     default = 10
+//     18 = 60
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct File {
+    rounds: Option<u32>,
+    warmup: Option<u32>,
+    inputs: Option<PathBuf>,
+    baselines: Option<PathBuf>,
+    log: Option<String>,
+    #[serde(default)]
+    report: ReportSection,
+    // Seconds per part, keyed by `default` or a day
+    #[serde(default)]
+    timeouts: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ReportSection {
+    format: Option<Format>,
+    path: Option<PathBuf>,
+}
+
+impl Config {
+    // Loads `path` if given, otherwise the first config file that exists in the working
+    // directory or the XDG config directory, or no config at all
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match Self::find() {
+                Some(path) => path,
+                None => return Ok(Self::default()),
+            },
+        };
+
+        let source = std::fs::read_to_string(&path)
+            .map_err(|err| Error::config(&path, format!("can't read: {err}")))?;
+        let mut config = Self::parse(&source).map_err(|message| Error::config(&path, message))?;
+
+        // Relative directories are relative to the config file, wherever the runner starts
+        if let Some(directory) = path.parent() {
+            for relative in [config.inputs.as_mut(), config.baselines.as_mut()]
+                .into_iter()
+                .flatten()
+            {
+                *relative = directory.join(&*relative);
+            }
+        }
+
+        Ok(config)
+    }
+
+    fn find() -> Option<PathBuf> {
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")));
+
+        std::iter::once(PathBuf::from(FILE_NAME))
+            .chain(config_dir.map(|dir| dir.join("aoc2019").join("config.toml")))
+            .find(|path| path.is_file())
+    }
+
+    fn parse(source: &str) -> core::result::Result<Self, String> {
+        let file = toml::from_str::<File>(source).map_err(|err| err.to_string())?;
+
+        if file.rounds == Some(0) {
+            return Err("rounds must be at least 1".to_string());
+        }
+
+        if let Some(log) = file.log.as_deref() {
+            EnvFilter::try_new(log).map_err(|err| format!("invalid log filter {log:?}: {err}"))?;
+        }
+
+        let mut timeout = None;
+        let mut day_timeouts = HashMap::new();
+        for (key, &seconds) in file.timeouts.iter() {
+            if !seconds.is_finite() || seconds <= 0.0 {
+                return Err(format!(
+                    "invalid timeout {seconds} for {key:?}, expected seconds"
+                ));
+            }
+
+            let duration = Duration::from_secs_f64(seconds);
+            match key.as_str() {
+                "default" => timeout = Some(duration),
+                day => {
+                    let day = day.parse::<u32>().map_err(|_| {
+                        format!("invalid timeout key {day:?}, expected \"default\" or a day")
+                    })?;
+                    day_timeouts.insert(day, duration);
+                }
+            }
+        }
+
+        Ok(Self {
+            rounds: file.rounds,
+            warmup: file.warmup,
+            format: file.report.format,
+            report: file.report.path,
+            inputs: file.inputs,
+            baselines: file.baselines,
+            log: file.log,
+            timeout,
+            day_timeouts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn test_parse() {
+        aoc2019::util::test::setup_tracing();
+
+        let config = Config::parse(
+            r#"
+            rounds = 10
+            warmup = 2
+            inputs = "../aoc-inputs"
+            baselines = "../aoc-baselines"
+            log = "aoc2019=debug"
+
+            [report]
+            format = "json"
+            path = "target/report.json"
+
+            [timeouts]
+            default = 10
+            18 = 0.5
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config,
+            Config {
+                rounds: Some(10),
+                warmup: Some(2),
+                format: Some(Format::Json),
+                report: Some(PathBuf::from("target/report.json")),
+                inputs: Some(PathBuf::from("../aoc-inputs")),
+                baselines: Some(PathBuf::from("../aoc-baselines")),
+                log: Some("aoc2019=debug".to_string()),
+                timeout: Some(Duration::from_secs(10)),
+                day_timeouts: HashMap::from([(18, Duration::from_millis(500))]),
+            }
+        );
+        assert_eq!(Config::parse(""), Ok(Config::default()));
+    }
+
+    #[rstest]
+    #[case("round = 3", "unknown field `round`")]
+    #[case("rounds = \"3\"", "invalid type")]
+    #[case("rounds = 0", "rounds must be at least 1")]
+    #[case("[report]\nformat = \"xml\"", "unknown variant `xml`")]
+    #[case("log = \"aoc2019=loud\"", "invalid log filter")]
+    #[case("[timeouts]\nday18 = 60", "invalid timeout key \"day18\"")]
+    #[case("[timeouts]\n18 = -1", "invalid timeout -1 for \"18\"")]
+    fn test_parse_error(#[case] source: &str, #[case] expected: &str) {
+        aoc2019::util::test::setup_tracing();
+        let message = Config::parse(source).unwrap_err();
+        assert!(message.contains(expected), "{message}");
+    }
+
+    #[rstest]
+    fn test_load() {
+        aoc2019::util::test::setup_tracing();
+
+        let path = std::env::temp_dir().join(format!("config-{}.toml", std::process::id()));
+        std::fs::write(&path, "rounds = 0\n").unwrap();
+        let invalid = Config::load(Some(&path));
+        std::fs::remove_file(&path).unwrap();
+        let missing = Config::load(Some(&path));
+
+        let message = invalid.unwrap_err().to_string();
+        assert!(message.contains(&path.display().to_string()), "{message}");
+        assert!(message.contains("rounds must be at least 1"), "{message}");
+        assert!(missing.unwrap_err().to_string().contains("can't read"));
+    }
+
+    #[rstest]
+    fn test_load_relative_inputs() -> Result<()> {
+        aoc2019::util::test::setup_tracing();
+
+        let directory = std::env::temp_dir().join(format!("config-{}", std::process::id()));
+        std::fs::create_dir_all(&directory)?;
+        let path = directory.join(FILE_NAME);
+        std::fs::write(&path, "inputs = \"../aoc-inputs\"\n")?;
+        let config = Config::load(Some(&path));
+        std::fs::write(&path, "inputs = \"/data/aoc\"\n")?;
+        let absolute = Config::load(Some(&path));
+        std::fs::remove_dir_all(&directory)?;
+
+        assert_eq!(config?.inputs, Some(directory.join("../aoc-inputs")));
+        assert_eq!(absolute?.inputs, Some(PathBuf::from("/data/aoc")));
+        Ok(())
+    }
+}
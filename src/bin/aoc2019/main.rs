@@ -0,0 +1,471 @@
+use std::{
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use clap::{Parser, Subcommand};
+use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+
+use aoc2019::{
+    benchmark::{Rounds, Sampling},
+    intcode::{
+        fuzz::{self, Differential},
+        Intcode,
+    },
+    memory::{self, CountingAllocator},
+    puzzle::{self, Input},
+    report::{Format, Report},
+    selection::Selection,
+    verify,
+};
+
+use crate::{
+    config::Config,
+    output::Output,
+    runner::{select, Options, Timeouts},
+};
+
+mod chart;
+mod compare;
+mod config;
+mod output;
+mod runner;
+mod scaffold;
+mod visualize;
+mod watch;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("No puzzle matches {selector}")]
+    NoSuchPuzzle { selector: String },
+    #[error("None of the selected parts is left after --part")]
+    NothingSelected,
+    #[error("An input file is for a single day, but {count} days are selected")]
+    InputForManyDays { count: usize },
+    #[error("Can't watch stdin for changes")]
+    WatchStdin,
+    #[error("Day {day} already exists")]
+    DayExists { day: u32 },
+    #[error("Config error in {}: {message}", path.display())]
+    Config { path: PathBuf, message: String },
+    #[error("Scaffold error: {message}")]
+    Scaffold { message: String },
+    #[error("Verification failed for {failed} part(s)")]
+    Verification { failed: usize },
+    #[error("Comparing against a baseline needs at least {required} rounds, got {rounds}")]
+    TooFewRounds { rounds: u32, required: usize },
+    #[error("Interpreters diverged on the program generated from seed {seed}")]
+    Divergence { seed: u64 },
+    #[error("No baseline named {name}")]
+    NoSuchBaseline { name: String },
+    #[error("{count} regression(s) above {threshold}%")]
+    Regression { count: usize, threshold: f64 },
+    #[error(transparent)]
+    Library(#[from] aoc2019::Error),
+    #[error(transparent)]
+    Puzzle(#[from] puzzle::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl Error {
+    fn config(path: &Path, message: impl Into<String>) -> Self {
+        Self::Config {
+            path: path.to_path_buf(),
+            message: message.into(),
+        }
+    }
+
+    fn scaffold(message: impl Into<String>) -> Self {
+        Self::Scaffold {
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// Days and parts to run, such as `1-5,9,12-` or `7:2`, instead of every day
+    #[arg(value_name = "DAYS")]
+    selection: Option<Selection>,
+    /// Only run this part of the selected days
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..=2))]
+    part: Option<u32>,
+    /// Read the puzzle input of the one selected day from a file, or from stdin with `-`
+    #[arg(
+        short,
+        long,
+        value_name = "PATH",
+        requires = "selection",
+        conflicts_with = "jobs"
+    )]
+    input: Option<PathBuf>,
+    /// Read the puzzle input from example file `inputs/dayNN/test.N.txt`
+    #[arg(short, long, value_name = "N", conflicts_with = "input")]
+    example: Option<usize>,
+    /// Benchmarking rounds [default: 1]
+    #[arg(short = 'r', long = "rounds", value_parser = clap::value_parser!(u32).range(1..))]
+    rounds: Option<u32>,
+    /// Untimed rounds to run before benchmarking [default: 0]
+    #[arg(short = 'w', long = "warmup")]
+    warmup: Option<u32>,
+    /// Sample until the timings are precise enough instead of a fixed number of rounds
+    #[arg(long, conflicts_with = "rounds")]
+    adaptive: bool,
+    /// Target relative 95% confidence interval of the mean in adaptive mode, in percent
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        default_value_t = 1.0,
+        value_parser = parse_positive,
+        requires = "adaptive"
+    )]
+    precision: f64,
+    /// Time budget per part in adaptive mode, in seconds
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 5.0,
+        value_parser = parse_positive,
+        requires = "adaptive"
+    )]
+    budget: f64,
+    /// Count allocations and peak live memory of the first timed run
+    #[arg(long)]
+    memory: bool,
+    /// Time limit for each part in seconds, after which the remaining puzzles still run
+    #[arg(long, value_name = "SECONDS", value_parser = parse_positive)]
+    timeout: Option<f64>,
+    /// Time limit for the parts of one day, overriding `--timeout`
+    #[arg(long, value_name = "DAY=SECONDS", value_parser = parse_day_timeout)]
+    day_timeout: Vec<(u32, f64)>,
+    /// Run this many puzzles concurrently, for quick verification passes
+    #[arg(
+        short,
+        long,
+        value_name = "N",
+        conflicts_with_all = [
+            "rounds", "warmup", "adaptive", "memory", "PATH", "baseline", "save_baseline"
+        ]
+    )]
+    jobs: Option<NonZeroUsize>,
+    /// Re-run the selected parts and their examples whenever their input files change
+    #[arg(long, conflicts_with_all = ["jobs", "baseline", "save_baseline"])]
+    watch: bool,
+    /// Show the selected days' visualisations instead of solving them
+    #[arg(long, conflicts_with_all = ["jobs", "watch"])]
+    visualize: bool,
+    /// Frames per second of a visualisation shown in the terminal
+    #[arg(
+        long,
+        value_name = "RATE",
+        default_value_t = 20,
+        value_parser = clap::value_parser!(u32).range(1..),
+        requires = "visualize"
+    )]
+    fps: u32,
+    /// Write the frames of a visualisation to numbered files in this directory
+    #[arg(long, value_name = "DIR", requires = "visualize")]
+    frames: Option<PathBuf>,
+    /// How to print the results, with `jsonl` writing one JSON object per day and part
+    #[arg(long, value_enum, default_value_t = Output::Text, conflicts_with = "watch")]
+    output: Output,
+    /// Optional benchmark report output location
+    #[arg(short = 'o', long = "out", id = "PATH")]
+    report: Option<PathBuf>,
+    /// Benchmark report format [default: csv]
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+    /// Save the benchmark report as a named baseline
+    #[arg(long, value_name = "NAME")]
+    save_baseline: Option<String>,
+    /// Compare the benchmark against a named baseline
+    #[arg(long, value_name = "NAME")]
+    baseline: Option<String>,
+    /// Slowdown in percent above which a significant change counts as a regression
+    #[arg(
+        long,
+        value_name = "PERCENT",
+        default_value_t = 5.0,
+        requires = "baseline"
+    )]
+    threshold: f64,
+    /// Check results against the checked-in answers, or another expected-answers file
+    #[arg(
+        long,
+        value_name = "ANSWERS",
+        num_args = 0..=1,
+        default_missing_value = verify::ANSWERS_FILE
+    )]
+    verify: Option<PathBuf>,
+    /// Read option defaults from this file instead of `aoc2019.toml` or
+    /// `$XDG_CONFIG_HOME/aoc2019/config.toml`
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+    /// Defaults for the options not given on the command line
+    #[arg(skip)]
+    defaults: Config,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Render saved CSV benchmark reports as a standalone HTML chart
+    Chart {
+        /// Reports to chart, compared against the first one
+        #[arg(required = true)]
+        reports: Vec<PathBuf>,
+        /// Chart output location
+        #[arg(short = 'o', long = "out", default_value = "report.html")]
+        out: PathBuf,
+    },
+    /// Create and register a new day from the template
+    New {
+        /// Day to create
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+        day: u32,
+    },
+    /// Run two Intcode interpreters side by side on generated programs and print a minimised
+    /// reproducer of the first divergence
+    Fuzz {
+        /// Seed of the first generated program
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Number of programs to generate
+        #[arg(long, default_value_t = 1000)]
+        cases: u64,
+        /// Code cells of each generated program, at most 1024
+        #[arg(long, default_value_t = 256)]
+        size: usize,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
+    let mut args = Args::parse();
+    args.defaults = Config::load(args.config.as_deref())?;
+    trace(args.defaults.log.as_deref());
+
+    if let Some(command) = args.command.as_ref() {
+        return Ok(command.run()?);
+    }
+
+    let parts = match args.part {
+        Some(1) => [true, false],
+        Some(2) => [false, true],
+        _ => [true, true],
+    };
+
+    if args.memory {
+        memory::enable();
+    }
+
+    let sampling = args.sampling();
+    let input = Input::new(args.input.clone(), args.example);
+
+    let mut puzzles = puzzle::registry(sampling);
+    if let Some(directory) = args.defaults.inputs.as_deref() {
+        puzzles = puzzles.with_input_dir(directory);
+    }
+    let selection = args.selection.clone().unwrap_or_default();
+    let selected = select(&puzzles, &selection, parts)?;
+    if matches!(input, Input::File(_) | Input::Stdin) && selected.len() > 1 {
+        return Err(Error::InputForManyDays {
+            count: selected.len(),
+        }
+        .into());
+    }
+
+    if args.watch {
+        return Ok(runner::watch(&selected, &input, &args.timeouts())?);
+    }
+
+    if args.visualize {
+        return Ok(runner::visualize(
+            &selected,
+            &input,
+            args.frames.as_deref(),
+            args.fps,
+        )?);
+    }
+
+    Ok(runner::run(&selected, &input, &args.options(sampling))?)
+}
+
+impl Command {
+    fn run(&self) -> Result<()> {
+        match self {
+            Self::Chart { reports, out } => {
+                let reports = reports
+                    .iter()
+                    .map(|path| {
+                        let name = path
+                            .file_stem()
+                            .unwrap_or(path.as_os_str())
+                            .to_string_lossy()
+                            .to_string();
+                        Ok((name, Report::load_from(path)?))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                chart::save(&reports, out)?;
+                println!("Saved chart to {}", out.display());
+                Ok(())
+            }
+            Self::New { day } => {
+                for path in scaffold::new_day(Path::new("."), *day)? {
+                    println!("Wrote {}", path.display());
+                }
+                Ok(())
+            }
+            Self::Fuzz { seed, cases, size } => {
+                // Optimised interpreters go on the right, checked against the plain one
+                let differential = Differential::new(
+                    |program| Intcode::new(program),
+                    |program| Intcode::new(program),
+                    fuzz::MAX_STEPS,
+                );
+                if let Some((seed, divergence)) = differential.search(*seed..seed + cases, *size) {
+                    println!("Seed {seed}: {divergence}");
+                    return Err(Error::Divergence { seed });
+                }
+
+                println!("Interpreters agree on {cases} program(s)");
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Args {
+    fn timeouts(&self) -> Timeouts {
+        // The command line overrides the config's timeouts one day at a time
+        let mut days = self.defaults.day_timeouts.clone();
+        days.extend(
+            self.day_timeout
+                .iter()
+                .map(|&(puzzle, seconds)| (puzzle, Duration::from_secs_f64(seconds))),
+        );
+
+        Timeouts {
+            default: self
+                .timeout
+                .map(Duration::from_secs_f64)
+                .or(self.defaults.timeout),
+            days,
+        }
+    }
+
+    fn sampling(&self) -> Sampling {
+        // `--jobs` runs every part once, so the configured rounds don't apply to it
+        let defaults = match self.jobs {
+            Some(_) => &Config::default(),
+            None => &self.defaults,
+        };
+
+        let rounds = if self.adaptive {
+            Rounds::Adaptive {
+                precision: self.precision / 100.0,
+                budget: Duration::from_secs_f64(self.budget),
+            }
+        } else {
+            Rounds::Fixed(self.rounds.or(defaults.rounds).unwrap_or(1))
+        };
+
+        Sampling {
+            warmup: self.warmup.or(defaults.warmup).unwrap_or(0),
+            rounds,
+        }
+    }
+
+    fn options(&self, sampling: Sampling) -> Options<'_> {
+        let report = self.report.as_deref().or(self.defaults.report.as_deref());
+        let format = self.format.or(self.defaults.format).unwrap_or_default();
+
+        Options {
+            sampling,
+            timeouts: self.timeouts(),
+            jobs: self.jobs,
+            output: self.output,
+            report: report.map(|path| (path, format)),
+            baselines: self
+                .defaults
+                .baselines
+                .as_deref()
+                .unwrap_or(Path::new(compare::BASELINE_DIR)),
+            save_baseline: self.save_baseline.as_deref(),
+            baseline: self.baseline.as_deref(),
+            threshold: self.threshold,
+            answers: self.verify.as_deref(),
+        }
+    }
+}
+
+fn parse_positive(value: &str) -> core::result::Result<f64, String> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite() && *value > 0.0)
+        .ok_or_else(|| format!("expected a positive number, got {value:?}"))
+}
+
+fn parse_day_timeout(value: &str) -> core::result::Result<(u32, f64), String> {
+    let (puzzle, seconds) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected DAY=SECONDS, got {value:?}"))?;
+    let puzzle = puzzle
+        .parse()
+        .map_err(|_| format!("invalid day {puzzle:?}"))?;
+    let seconds = parse_positive(seconds)?;
+
+    Ok((puzzle, seconds))
+}
+
+// Logs according to `AOC_LOG`, or the configured filter when it isn't set
+pub fn trace(filter: Option<&str>) {
+    let filter = match filter {
+        Some(filter) if std::env::var_os("AOC_LOG").is_none() => EnvFilter::new(filter),
+        _ => EnvFilter::from_env("AOC_LOG"),
+    };
+
+    tracing_subscriber::registry()
+        .with(fmt::layer())
+        .with(filter)
+        .init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{error::ErrorKind, CommandFactory};
+    use rstest::*;
+
+    #[rstest]
+    fn test_args() {
+        aoc2019::util::test::setup_tracing();
+        Args::command().debug_assert();
+    }
+
+    #[rstest]
+    #[case(&["--out", "report.csv"])]
+    #[case(&["--memory"])]
+    #[case(&["--rounds", "3"])]
+    #[case(&["--baseline", "main"])]
+    #[case(&["--save-baseline", "main"])]
+    fn test_jobs_conflicts(#[case] args: &[&str]) {
+        aoc2019::util::test::setup_tracing();
+
+        let args = ["aoc2019", "--jobs", "2"].iter().chain(args);
+        let Err(err) = Args::try_parse_from(args) else {
+            panic!("expected a conflict with --jobs");
+        };
+        assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+    }
+}
@@ -0,0 +1,223 @@
+use std::io::Write;
+
+use serde::Serialize;
+
+use aoc2019::{benchmark::RuntimeStats, memory::Allocations, puzzle, verify::Verdict};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Output {
+    // `Day NN part M (<stats>): <answer>` lines for people
+    #[default]
+    Text,
+    // One JSON object per day and part, with the human-readable summary moved to stderr
+    Jsonl,
+}
+
+// Result of one day and part as a single JSON line. Times are in milliseconds.
+#[derive(Debug, Serialize)]
+pub struct Record<'a> {
+    pub day: u32,
+    pub part: u32,
+    pub source: &'a str,
+    pub answer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verdict: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected: Option<&'a str>,
+    pub parse: Option<Stats>,
+    pub solve: Option<Stats>,
+    pub error: Option<ErrorRecord>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Stats {
+    pub runs: usize,
+    pub median: f64,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocations: Option<Allocations>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ErrorRecord {
+    pub kind: String,
+    pub message: String,
+}
+
+impl Output {
+    // Human-readable summary lines, which move to stderr when stdout carries JSON lines
+    pub fn status(self, message: std::fmt::Arguments) {
+        match self {
+            Self::Text => println!("{message}"),
+            Self::Jsonl => eprintln!("{message}"),
+        }
+    }
+}
+
+impl<'a> Record<'a> {
+    pub fn new(
+        day: u32,
+        part: u32,
+        source: &'a str,
+        result: &'a aoc2019::benchmark::Result,
+        verdict: Option<&'a Verdict>,
+    ) -> Self {
+        let mut record = Self {
+            day,
+            part,
+            source,
+            answer: None,
+            verdict: None,
+            expected: None,
+            parse: None,
+            solve: None,
+            error: None,
+        };
+
+        match result {
+            Ok((timings, answer)) => {
+                record.answer = Some(answer_text(answer).to_string());
+                record.parse = Some(Stats::new(&timings.parse, timings.parse_allocations));
+                record.solve = Some(Stats::new(&timings.solve, timings.solve_allocations));
+            }
+            Err(err) => record.error = Some(ErrorRecord::new(err)),
+        }
+
+        match verdict {
+            Some(Verdict::Pass) => record.verdict = Some("pass"),
+            Some(Verdict::Fail { expected }) => {
+                record.verdict = Some("fail");
+                record.expected = Some(expected);
+            }
+            Some(Verdict::Missing) => record.verdict = Some("missing"),
+            None => {}
+        }
+
+        record
+    }
+
+    pub fn write(&self, mut writer: impl Write) -> std::io::Result<()> {
+        serde_json::to_writer(&mut writer, self)?;
+        writeln!(writer)
+    }
+}
+
+impl Stats {
+    fn new(stats: &RuntimeStats, allocations: Option<Allocations>) -> Self {
+        let runs = stats.runs();
+        let millis = |duration: &std::time::Duration| duration.as_secs_f64() * 1000.0;
+
+        Self {
+            runs: runs.len(),
+            median: millis(&stats.median()),
+            mean: runs.iter().map(millis).sum::<f64>() / runs.len() as f64,
+            min: runs.iter().map(millis).fold(f64::INFINITY, f64::min),
+            max: runs.iter().map(millis).fold(f64::NEG_INFINITY, f64::max),
+            allocations,
+        }
+    }
+}
+
+impl ErrorRecord {
+    fn new(err: &puzzle::Error) -> Self {
+        Self {
+            kind: err.kind(),
+            message: err.to_string(),
+        }
+    }
+}
+
+// Multi-line answers such as rendered bitmaps are wrapped in `OnNewLine` so they start below the
+// label on a terminal. That newline is layout, not part of the answer.
+fn answer_text(answer: &str) -> &str {
+    answer.strip_prefix('\n').unwrap_or(answer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc2019::{
+        benchmark::Timings,
+        util::{
+            bitmap::{Bitmap, BoxDisplay},
+            display::OnNewLine,
+            position::Position,
+        },
+    };
+    use rstest::*;
+    use std::time::Duration;
+
+    fn timings() -> Timings {
+        Timings {
+            parse: Duration::from_micros(500).into(),
+            solve: vec![Duration::from_millis(1), Duration::from_millis(3)].into(),
+            parse_allocations: None,
+            solve_allocations: None,
+        }
+    }
+
+    fn line(record: &Record) -> serde_json::Value {
+        let mut buffer = Vec::new();
+        record.write(&mut buffer).unwrap();
+        let text = String::from_utf8(buffer).unwrap();
+        assert_eq!(text.matches('\n').count(), 1, "{text}");
+        serde_json::from_str(&text).unwrap()
+    }
+
+    #[rstest]
+    fn test_answer() {
+        aoc2019::util::test::setup_tracing();
+
+        let result = Ok((timings(), "42".to_string()));
+        let verdict = Verdict::Fail {
+            expected: "41".to_string(),
+        };
+        let value = line(&Record::new(
+            5,
+            1,
+            "inputs/day05/input.txt",
+            &result,
+            Some(&verdict),
+        ));
+
+        assert_eq!(value["day"], 5);
+        assert_eq!(value["answer"], "42");
+        assert_eq!(value["verdict"], "fail");
+        assert_eq!(value["expected"], "41");
+        assert_eq!(value["parse"]["median"], 0.5);
+        assert_eq!(value["solve"]["runs"], 2);
+        assert_eq!(value["solve"]["mean"], 2.0);
+        assert!(value["error"].is_null());
+    }
+
+    #[rstest]
+    fn test_bitmap_answer() {
+        aoc2019::util::test::setup_tracing();
+
+        let mut bitmap = Bitmap::new(3, 2);
+        bitmap.put(&Position::new(0, 0), true);
+        bitmap.put(&Position::new(2, 1), true);
+        let answer = OnNewLine(BoxDisplay(bitmap)).to_string();
+
+        let result = Ok((timings(), answer));
+        let value = line(&Record::new(8, 2, "<stdin>", &result, None));
+
+        assert_eq!(value["answer"], "\u{2580} \u{2584}");
+        assert!(value.get("verdict").is_none());
+    }
+
+    #[rstest]
+    fn test_error() {
+        aoc2019::util::test::setup_tracing();
+
+        let result = Err(puzzle::Error::Timeout(Duration::from_secs(2)));
+        let value = line(&Record::new(7, 2, "inputs/day07/input.txt", &result, None));
+
+        assert!(value["answer"].is_null());
+        assert!(value["solve"].is_null());
+        assert_eq!(value["error"]["kind"], "timeout");
+        assert_eq!(value["error"]["message"], "timed out after 2.000s");
+    }
+}
@@ -0,0 +1,397 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    num::NonZeroUsize,
+    path::Path,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use rayon::prelude::*;
+
+use aoc2019::{
+    benchmark::{self, DurationFormatter, Rounds, Sampling},
+    puzzle::{self, Input, Puzzle, Registry},
+    report::{Format, Metadata, Report},
+    selection::Selection,
+    verify::{Answers, Summary},
+};
+
+use crate::{
+    compare,
+    output::{Output, Record},
+    visualize, watch, Error, Result,
+};
+
+// Time limit for each part, with overrides for known-slow days
+#[derive(Debug, Default)]
+pub struct Timeouts {
+    pub default: Option<Duration>,
+    pub days: HashMap<u32, Duration>,
+}
+
+impl Timeouts {
+    pub fn get(&self, puzzle: u32) -> Option<Duration> {
+        self.days.get(&puzzle).copied().or(self.default)
+    }
+}
+
+// The selected puzzles with the parts to run, leaving out puzzles without any selected part
+pub fn select<'a>(
+    puzzles: &'a Registry,
+    selection: &Selection,
+    parts: [bool; 2],
+) -> Result<Vec<(&'a Puzzle, [bool; 2])>> {
+    let ids = puzzles.iter().map(Puzzle::id).collect::<Vec<_>>();
+    if let Some(selector) = selection.unmatched(&ids).next() {
+        return Err(Error::NoSuchPuzzle { selector });
+    }
+
+    let selected = puzzles
+        .iter()
+        .map(|puzzle| {
+            let selected = selection.parts(puzzle.id());
+            (puzzle, [selected[0] && parts[0], selected[1] && parts[1]])
+        })
+        .filter(|(_, parts)| parts.iter().any(|&part| part))
+        .collect::<Vec<_>>();
+
+    // Such as `7:2` with `--part 1`
+    if selected.is_empty() {
+        return Err(Error::NothingSelected);
+    }
+
+    Ok(selected)
+}
+
+pub fn run_all(
+    selected: &[(&Puzzle, [bool; 2])],
+    input: &Input,
+    timeouts: &Timeouts,
+    mut visitor: impl FnMut(u32, u32, &str, benchmark::Result) -> aoc2019::Result<()>,
+) -> Result<()> {
+    for (puzzle, parts) in selected.iter() {
+        puzzle.run(input, *parts, timeouts.get(puzzle.id()), &mut visitor)?;
+    }
+
+    Ok(())
+}
+
+type Outcome = (u32, u32, String, benchmark::Result);
+
+// Runs the puzzles on a thread pool of `jobs` threads, visiting the results in day order as soon
+// as every earlier day has finished
+pub fn run_parallel(
+    selected: &[(&Puzzle, [bool; 2])],
+    input: &Input,
+    timeouts: &Timeouts,
+    jobs: NonZeroUsize,
+    mut visitor: impl FnMut(u32, u32, &str, benchmark::Result) -> aoc2019::Result<()>,
+) -> Result<()> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.get())
+        .build()
+        .expect("thread pool can be built");
+
+    let (sender, receiver) = mpsc::channel::<(usize, aoc2019::Result<Vec<Outcome>>)>();
+
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            pool.install(|| {
+                selected.par_iter().enumerate().for_each_with(
+                    sender,
+                    |sender, (idx, (puzzle, parts))| {
+                        let mut outcomes = Vec::new();
+                        let result = puzzle.run(
+                            input,
+                            *parts,
+                            timeouts.get(puzzle.id()),
+                            |puzzle, part, source, result| {
+                                outcomes.push((puzzle, part, source.to_string(), result));
+                                Ok(())
+                            },
+                        );
+                        // The receiver only hangs up after an error, when the rest is not needed
+                        let _ = sender.send((idx, result.map(|_| outcomes)));
+                    },
+                )
+            })
+        });
+
+        let mut finished = BTreeMap::new();
+        let mut next = 0;
+        for (idx, outcomes) in receiver {
+            finished.insert(idx, outcomes);
+
+            while let Some(outcomes) = finished.remove(&next) {
+                for (puzzle, part, source, result) in outcomes? {
+                    visitor(puzzle, part, &source, result)?;
+                }
+                next += 1;
+            }
+        }
+
+        Ok(())
+    })
+}
+
+// How to run the selected parts and what to do with their results
+pub struct Options<'a> {
+    pub sampling: Sampling,
+    pub timeouts: Timeouts,
+    pub jobs: Option<NonZeroUsize>,
+    pub output: Output,
+    pub report: Option<(&'a Path, Format)>,
+    pub baselines: &'a Path,
+    pub save_baseline: Option<&'a str>,
+    pub baseline: Option<&'a str>,
+    pub threshold: f64,
+    pub answers: Option<&'a Path>,
+}
+
+// Runs the selected parts, printing every result and the totals, then saves the report and
+// checks the results against the baseline and the expected answers
+pub fn run(selected: &[(&Puzzle, [bool; 2])], input: &Input, options: &Options) -> Result<()> {
+    if let Rounds::Fixed(rounds) = options.sampling.rounds {
+        let compares = options.baseline.is_some() || options.save_baseline.is_some();
+        if compares && (rounds as usize) < compare::MIN_SAMPLES {
+            return Err(Error::TooFewRounds {
+                rounds,
+                required: compare::MIN_SAMPLES,
+            });
+        }
+    }
+
+    let start = Instant::now();
+
+    let baseline = options
+        .baseline
+        .map(|name| compare::load_baseline(options.baselines, name))
+        .transpose()?;
+
+    let wants_report =
+        options.report.is_some() || options.save_baseline.is_some() || baseline.is_some();
+    let mut report = wants_report.then(|| Report::new(Metadata::new(options.sampling)));
+
+    let answers = options.answers.map(Answers::load).transpose()?;
+    let mut summary = Summary::default();
+
+    let mut sum_of_medians = Duration::ZERO;
+    let mut timed_parts = 0;
+    let mut timed_out = false;
+    let visitor = |puzzle, part, source: &str, result: benchmark::Result| {
+        let label = match input {
+            Input::Default => format!("Day {puzzle:02} part {part}"),
+            _ => format!("Day {puzzle:02} part {part} [{source}]"),
+        };
+
+        let verdict = match (&result, answers.as_ref()) {
+            (Ok((_, result)), Some(answers)) => Some(answers.check(puzzle, part, result)),
+            _ => None,
+        };
+
+        if options.output == Output::Jsonl {
+            Record::new(puzzle, part, source, &result, verdict.as_ref())
+                .write(std::io::stdout().lock())?;
+        }
+
+        match result {
+            Ok((stats, result)) => {
+                if options.output == Output::Text {
+                    match verdict.as_ref() {
+                        Some(verdict) => {
+                            println!("{label} ({stats}): {}", verdict.describe(&result))
+                        }
+                        None => println!("{label} ({stats}): {result}"),
+                    }
+                }
+                if let Some(verdict) = verdict.as_ref() {
+                    summary.record(verdict);
+                }
+                sum_of_medians += stats.solve.median();
+                timed_parts += 1;
+
+                if let Some(report) = report.as_mut() {
+                    report.push_entry(puzzle, part, source, &stats);
+                }
+            }
+            Err(err) => {
+                if options.output == Output::Text {
+                    println!("{label}: {err}");
+                }
+                summary.record_error();
+
+                if let puzzle::Error::Timeout(timeout) = &err {
+                    // The timed-out part keeps running in the background
+                    if !std::mem::replace(&mut timed_out, true) {
+                        eprintln!(
+                            "Warning: day {puzzle:02} is still running after timing out, \
+                             so later timings and allocation counts are skewed"
+                        );
+                    }
+                    if let Some(report) = report.as_mut() {
+                        report.push_timeout(puzzle, part, source, *timeout);
+                    }
+                }
+            }
+        }
+        Ok(())
+    };
+
+    if let Some(jobs) = options.jobs {
+        eprintln!("Warning: puzzles run concurrently with --jobs, so timings are not comparable");
+        run_parallel(selected, input, &options.timeouts, jobs, visitor)?;
+    } else {
+        run_all(selected, input, &options.timeouts, visitor)?;
+    }
+
+    let total = start.elapsed();
+
+    // Only parts that ran successfully have timings to add up
+    if !options.sampling.is_single() {
+        options.output.status(format_args!(
+            "Sum of median solve times over {timed_parts} part(s): {}",
+            DurationFormatter(sum_of_medians),
+        ));
+    } else {
+        options.output.status(format_args!(
+            "Sum of solve times over {timed_parts} part(s): {}",
+            DurationFormatter(sum_of_medians),
+        ));
+    }
+
+    options
+        .output
+        .status(format_args!("Total time: {}", DurationFormatter(total)));
+
+    if let Some(report) = report.as_ref() {
+        if let Some((path, format)) = options.report {
+            report.save_to(path, format)?;
+        }
+
+        if let Some(name) = options.save_baseline {
+            let path = compare::save_baseline(report, options.baselines, name)?;
+            options
+                .output
+                .status(format_args!("Saved baseline {name} to {}", path.display()));
+        }
+    }
+
+    if let (Some(name), Some(baseline), Some(report)) =
+        (options.baseline, baseline.as_ref(), report.as_ref())
+    {
+        let comparisons = compare::compare(baseline, report, options.threshold);
+        options
+            .output
+            .status(format_args!("Comparison with baseline {name}:"));
+        for comparison in comparisons.iter() {
+            options.output.status(format_args!("{comparison}"));
+        }
+
+        let regressions = comparisons
+            .iter()
+            .filter(|comparison| comparison.regression)
+            .count();
+        if regressions > 0 {
+            return Err(Error::Regression {
+                count: regressions,
+                threshold: options.threshold,
+            });
+        }
+    }
+
+    if answers.is_some() {
+        options
+            .output
+            .status(format_args!("Verification: {summary}"));
+
+        if summary.failed > 0 {
+            return Err(Error::Verification {
+                failed: summary.failed,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+// Re-runs the selected parts and their examples whenever their input files change
+pub fn watch(selected: &[(&Puzzle, [bool; 2])], input: &Input, timeouts: &Timeouts) -> Result<()> {
+    if *input == Input::Stdin {
+        return Err(Error::WatchStdin);
+    }
+
+    let files = watch::files(selected, input);
+    watch::watch(files, || {
+        run_all(selected, input, timeouts, |puzzle, part, _, result| {
+            match result {
+                Ok((stats, result)) => {
+                    println!("Day {puzzle:02} part {part} ({stats}): {result}")
+                }
+                Err(err) => println!("Day {puzzle:02} part {part}: {err}"),
+            }
+            Ok(())
+        })?;
+        watch::check_examples(selected);
+        Ok(())
+    })
+}
+
+// Shows the visualisations of the selected days in the terminal at `fps` frames per second, or
+// writes their frames to `frames`
+pub fn visualize(
+    selected: &[(&Puzzle, [bool; 2])],
+    input: &Input,
+    frames: Option<&Path>,
+    fps: u32,
+) -> Result<()> {
+    for (puzzle, _) in selected.iter() {
+        let result = match frames {
+            Some(directory) => {
+                let prefix = format!("day{:02}", puzzle.id());
+                let mut files = visualize::Files::new(directory, &prefix)?;
+                puzzle.visualize(input, &mut files).map(|()| {
+                    println!(
+                        "Wrote {} frame(s) of day {:02} to {}",
+                        files.written(),
+                        puzzle.id(),
+                        directory.display()
+                    )
+                })
+            }
+            None => puzzle.visualize(input, &mut visualize::Terminal::new(fps)),
+        };
+
+        if let Err(err) = result {
+            println!("Day {:02}: {err}", puzzle.id());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("7:2", [true, false], None)]
+    #[case("7:2", [false, true], Some(1))]
+    #[case("7", [true, false], Some(1))]
+    #[case("5,7:1", [false, true], Some(1))]
+    fn test_select(
+        #[case] selection: &str,
+        #[case] parts: [bool; 2],
+        #[case] expected: Option<usize>,
+    ) {
+        aoc2019::util::test::setup_tracing();
+
+        let puzzles = puzzle::registry(Sampling::default());
+        let selection = selection.parse::<Selection>().unwrap();
+        match (select(&puzzles, &selection, parts), expected) {
+            (Ok(selected), Some(count)) => assert_eq!(selected.len(), count),
+            (Err(Error::NothingSelected), None) => (),
+            (result, _) => panic!("unexpected selection {:?}", result.map(|s| s.len())),
+        }
+    }
+}
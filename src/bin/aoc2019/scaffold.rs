@@ -0,0 +1,177 @@
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
+
+use crate::{Error, Result};
+
+const TEMPLATE: &str = include_str!("../../puzzle/template/mod.rs");
+const GITIGNORE: &str = include_str!("../../../inputs/template/.gitignore");
+
+const REGISTRY: &str = "src/puzzle/mod.rs";
+const REGISTRY_START: &str = "puzzles! {";
+const MODULES_PER_LINE: usize = 10;
+
+// Creates `src/puzzle/dayNN/mod.rs` from the template, the `inputs/dayNN` directory with an
+// empty example and registers the module, returning the files that were written
+pub fn new_day(root: &Path, day: u32) -> Result<Vec<PathBuf>> {
+    let module = format!("day{day:02}");
+    let source_dir = root.join("src/puzzle").join(&module);
+    let input_dir = root.join("inputs").join(&module);
+
+    let registry_path = root.join(REGISTRY);
+    let registry = std::fs::read_to_string(&registry_path)?;
+    let registry = register(&registry, &module)?;
+
+    if source_dir.exists() {
+        return Err(Error::DayExists { day });
+    }
+
+    let source = day_source(day)?;
+
+    let mut written = Vec::new();
+    std::fs::create_dir_all(&source_dir)?;
+    let source_file = source_dir.join("mod.rs");
+    std::fs::write(&source_file, source)?;
+    written.push(source_file);
+
+    std::fs::create_dir_all(&input_dir)?;
+    for (name, contents) in [(".gitignore", GITIGNORE), ("test.0.txt", "")] {
+        let path = input_dir.join(name);
+        if !path.exists() {
+            std::fs::write(&path, contents)?;
+            written.push(path);
+        }
+    }
+
+    std::fs::write(&registry_path, registry)?;
+    written.push(registry_path);
+
+    Ok(written)
+}
+
+fn day_source(day: u32) -> Result<String> {
+    let replacements = [
+        ("const DAY: u32 = 0;", format!("const DAY: u32 = {day};")),
+        ("inputs/template/", format!("inputs/day{day:02}/")),
+    ];
+
+    let mut source = TEMPLATE.to_string();
+    for (from, to) in replacements {
+        if !source.contains(from) {
+            return Err(Error::scaffold(format!("template has no {from:?}")));
+        }
+        source = source.replace(from, &to);
+    }
+
+    Ok(source)
+}
+
+// Adds `module` to the `puzzles!` invocation, keeping the modules sorted
+fn register(registry: &str, module: &str) -> Result<String> {
+    let start = registry
+        .find(REGISTRY_START)
+        .ok_or_else(|| Error::scaffold("no puzzles! invocation"))?
+        + REGISTRY_START.len();
+    let end = start
+        + registry[start..]
+            .find('}')
+            .ok_or_else(|| Error::scaffold("unterminated puzzles! invocation"))?;
+
+    let mut modules = registry[start..end]
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|module| !module.is_empty())
+        .collect::<Vec<_>>();
+
+    if modules.contains(&module) {
+        let day = module.trim_start_matches("day").parse().unwrap_or_default();
+        return Err(Error::DayExists { day });
+    }
+
+    modules.push(module);
+    // The template stays first, and the zero padded days sort by name
+    modules.sort_by_key(|&module| (module != "template", module));
+
+    let newline = if registry.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let lines = modules
+        .chunks(MODULES_PER_LINE)
+        .map(|chunk| format!("    {},", chunk.join(", ")))
+        .join(newline);
+
+    Ok(format!(
+        "{}{newline}{lines}{newline}{}",
+        &registry[..start],
+        &registry[end..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    const REGISTRY_SOURCE: &str = "\
+mod intcode;
+
+puzzles! {
+    template, day01, day02, day03, day04, day05, day06, day07, day08, day09,
+    day10, day11, day12, day13, day14, day15, day16, day17,
+}
+
+pub const TEMPLATE: u32 = 0;
+";
+
+    #[rstest]
+    fn test_register() -> Result<()> {
+        aoc2019::util::test::setup_tracing();
+
+        let registry = register(REGISTRY_SOURCE, "day20")?;
+        assert!(registry
+            .contains("\n    day10, day11, day12, day13, day14, day15, day16, day17, day20,\n}\n"));
+
+        let registry = register(&registry, "day18")?;
+        let registry = register(&registry, "day19")?;
+        assert!(registry.contains("day17, day18, day19,\n    day20,\n}"));
+        assert!(registry.ends_with("\npub const TEMPLATE: u32 = 0;\n"));
+
+        let result = register(REGISTRY_SOURCE, "day05");
+        assert!(matches!(result, Err(Error::DayExists { day: 5 })));
+        Ok(())
+    }
+
+    #[rstest]
+    fn test_day_source() -> Result<()> {
+        aoc2019::util::test::setup_tracing();
+
+        let source = day_source(21)?;
+        assert!(source.contains("const DAY: u32 = 21;"));
+        assert!(source.contains("\"inputs/day21/input.txt\""));
+        assert!(source.contains("\"inputs/day21/test.{}.txt\""));
+        assert!(!source.contains("template"));
+        Ok(())
+    }
+
+    #[rstest]
+    fn test_new_day() -> Result<()> {
+        aoc2019::util::test::setup_tracing();
+
+        let root = std::env::temp_dir().join(format!("scaffold-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src/puzzle"))?;
+        std::fs::write(root.join(REGISTRY), REGISTRY_SOURCE)?;
+
+        let written = new_day(&root, 18);
+        let again = new_day(&root, 18);
+        let registry = std::fs::read_to_string(root.join(REGISTRY));
+        let example = root.join("inputs/day18/test.0.txt").exists();
+        std::fs::remove_dir_all(&root)?;
+
+        assert_eq!(written?.len(), 4);
+        assert!(matches!(again, Err(Error::DayExists { day: 18 })));
+        assert!(registry?.contains("day17, day18,"));
+        assert!(example);
+        Ok(())
+    }
+}
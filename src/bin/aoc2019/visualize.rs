@@ -0,0 +1,97 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use aoc2019::puzzle::{self, Frames};
+
+// Clears the screen and moves the cursor home before every frame
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+// Streams frames to stdout, no faster than `rate` frames per second
+pub struct Terminal {
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl Terminal {
+    pub fn new(rate: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / rate.max(1),
+            last: None,
+        }
+    }
+}
+
+impl Frames for Terminal {
+    fn frame(&mut self, frame: &dyn std::fmt::Display) -> puzzle::Result<()> {
+        if let Some(elapsed) = self.last.map(|last| last.elapsed()) {
+            std::thread::sleep(self.interval.saturating_sub(elapsed));
+        }
+        self.last = Some(Instant::now());
+
+        let mut stdout = std::io::stdout().lock();
+        writeln!(stdout, "{CLEAR}{frame}")?;
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+// Writes every frame to its own numbered file `<prefix>-NNNNN.txt` in `directory`
+pub struct Files {
+    directory: PathBuf,
+    prefix: String,
+    written: usize,
+}
+
+impl Files {
+    pub fn new(directory: &Path, prefix: &str) -> puzzle::Result<Self> {
+        std::fs::create_dir_all(directory)?;
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            prefix: prefix.to_string(),
+            written: 0,
+        })
+    }
+
+    pub fn written(&self) -> usize {
+        self.written
+    }
+}
+
+impl Frames for Files {
+    fn frame(&mut self, frame: &dyn std::fmt::Display) -> puzzle::Result<()> {
+        self.written += 1;
+        let path = self
+            .directory
+            .join(format!("{}-{:05}.txt", self.prefix, self.written));
+        std::fs::write(path, format!("{frame}\n"))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn test_files() -> puzzle::Result<()> {
+        aoc2019::util::test::setup_tracing();
+
+        let directory = std::env::temp_dir().join(format!("frames-{}", std::process::id()));
+        let mut files = Files::new(&directory, "day13")?;
+        files.frame(&"first")?;
+        files.frame(&"second\nframe")?;
+
+        let first = std::fs::read_to_string(directory.join("day13-00001.txt"));
+        let second = std::fs::read_to_string(directory.join("day13-00002.txt"));
+        std::fs::remove_dir_all(&directory)?;
+
+        assert_eq!(files.written(), 2);
+        assert_eq!(first?, "first\n");
+        assert_eq!(second?, "second\nframe\n");
+        Ok(())
+    }
+}
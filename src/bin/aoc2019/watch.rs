@@ -0,0 +1,183 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use aoc2019::puzzle::{Input, Puzzle};
+
+use crate::Result;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Input and example files of the selected parts. Files that don't exist yet are watched too, so
+// adding an example triggers a run.
+pub fn files(selected: &[(&Puzzle, [bool; 2])], input: &Input) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    for (puzzle, parts) in selected.iter() {
+        match input {
+            Input::Default => files.push(PathBuf::from(puzzle.source(input))),
+            Input::File(path) => files.push(path.clone()),
+            Input::Stdin => {}
+            Input::Example(which) => files.push(puzzle.example_file(*which)),
+        }
+
+        for example in puzzle.examples() {
+            if parts[example.part as usize - 1] {
+                files.push(puzzle.example_file(example.which));
+            }
+        }
+    }
+
+    files.sort();
+    files.dedup();
+    files
+}
+
+// Runs the examples of the selected parts whose input exists and prints the outcomes
+pub fn check_examples(selected: &[(&Puzzle, [bool; 2])]) {
+    for (puzzle, parts) in selected.iter() {
+        for example in puzzle.examples() {
+            if !parts[example.part as usize - 1] || !puzzle.example_file(example.which).exists() {
+                continue;
+            }
+
+            let label = format!(
+                "Day {:02} part {} example {}",
+                puzzle.id(),
+                example.part,
+                example.which
+            );
+            match puzzle.run_example(example) {
+                Ok((_, result)) if result == example.expected => println!("{label}: pass"),
+                Ok((_, result)) => {
+                    println!("{label}: FAIL, expected {}, got {result}", example.expected)
+                }
+                Err(err) => println!("{label}: {err}"),
+            }
+        }
+    }
+}
+
+// Calls `run` once and then again whenever one of `files` changes, polling their modification
+// times. Earlier runs stay on screen, separated by a header naming what changed.
+pub fn watch(files: Vec<PathBuf>, mut run: impl FnMut() -> Result<()>) -> Result<()> {
+    let mut watcher = Watcher::new(files);
+    let mut changed = Vec::new();
+
+    for round in 1.. {
+        if changed.is_empty() {
+            println!("──── run {round} ────");
+        } else {
+            let changed = changed
+                .iter()
+                .map(|path: &PathBuf| path.display().to_string())
+                .collect::<Vec<_>>();
+            println!("──── run {round}, changed {} ────", changed.join(", "));
+        }
+
+        if let Err(err) = run() {
+            println!("Error: {err}");
+        }
+        println!("Watching {} file(s) for changes", watcher.files.len());
+
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            changed = watcher.poll();
+            if !changed.is_empty() {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+struct Watcher {
+    files: Vec<PathBuf>,
+    modified: Vec<Option<SystemTime>>,
+}
+
+impl Watcher {
+    fn new(files: Vec<PathBuf>) -> Self {
+        let modified = files.iter().map(|path| modified(path)).collect();
+        Self { files, modified }
+    }
+
+    // Files that were created, changed or removed since the last poll
+    fn poll(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+
+        for (path, previous) in self.files.iter().zip(self.modified.iter_mut()) {
+            let current = modified(path);
+            if current != *previous {
+                *previous = current;
+                changed.push(path.clone());
+            }
+        }
+
+        changed
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc2019::{benchmark::Sampling, puzzle};
+    use rstest::*;
+
+    #[rstest]
+    fn test_poll() -> Result<()> {
+        aoc2019::util::test::setup_tracing();
+
+        let path = std::env::temp_dir().join(format!("watch-{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut watcher = Watcher::new(vec![path.clone()]);
+        assert!(watcher.poll().is_empty());
+
+        std::fs::write(&path, "1")?;
+        let created = watcher.poll();
+        assert!(watcher.poll().is_empty());
+
+        let file = std::fs::File::options().write(true).open(&path)?;
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1))?;
+        let modified = watcher.poll();
+
+        std::fs::remove_file(&path)?;
+        let removed = watcher.poll();
+
+        assert_eq!(created, std::slice::from_ref(&path));
+        assert_eq!(modified, std::slice::from_ref(&path));
+        assert_eq!(removed, [path]);
+        Ok(())
+    }
+
+    #[rstest]
+    fn test_files() {
+        aoc2019::util::test::setup_tracing();
+
+        let registry = puzzle::registry(Sampling::default());
+        let puzzle = registry.get(3).expect("day 3 is registered");
+        let files = files(&[(puzzle, [false, true])], &Input::Default);
+        let files = files
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            files,
+            [
+                "inputs/day03/input.txt",
+                "inputs/day03/test.0.txt",
+                "inputs/day03/test.1.txt",
+                "inputs/day03/test.2.txt",
+            ]
+        );
+    }
+}
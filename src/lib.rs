@@ -0,0 +1,67 @@
+// Advent of Code 2019 solutions and the Intcode VM, together with the tooling to benchmark them
+// and verify their answers. The `aoc2019` binary in `src/bin` adds the command line runner.
+
+pub mod benchmark;
+pub mod memory;
+pub mod puzzle;
+pub mod report;
+pub mod selection;
+pub mod util;
+pub mod verify;
+
+pub use puzzle::intcode;
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Answers error: line {line}: {message}")]
+    Answers { line: usize, message: String },
+    #[error("Can't read answers file {}", path.display())]
+    AnswersFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Report error: line {line}: {message}")]
+    ReportFormat { line: usize, message: String },
+    #[error(transparent)]
+    Puzzle(#[from] puzzle::Error),
+    #[error(transparent)]
+    Report(csv::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl Error {
+    fn answers(line: usize, message: impl Into<String>) -> Self {
+        Self::Answers {
+            line,
+            message: message.into(),
+        }
+    }
+
+    fn report(line: usize, message: impl Into<String>) -> Self {
+        Self::ReportFormat {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(value: csv::Error) -> Self {
+        match value.kind() {
+            csv::ErrorKind::Io(_) => {
+                let io = match value.into_kind() {
+                    csv::ErrorKind::Io(io) => io,
+                    _ => unreachable!(),
+                };
+
+                Self::Io(io)
+            }
+            _ => Self::Report(value),
+        }
+    }
+}
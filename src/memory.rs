@@ -0,0 +1,163 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering},
+};
+
+use serde::Serialize;
+
+// Binaries opt in by installing `CountingAllocator` as their global allocator, which the tests
+// do as well
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// Counting is off until `enable` is called, so runs without `--memory` only pay for one
+// relaxed load per allocation
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static COUNT: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+// Live bytes since the last reset, which goes negative when older memory is freed
+static LIVE: AtomicI64 = AtomicI64::new(0);
+static PEAK: AtomicI64 = AtomicI64::new(0);
+
+// The system allocator, counting allocations from every thread so rayon workers are included
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn allocated(size: usize) {
+        if ENABLED.load(Ordering::Relaxed) {
+            COUNT.fetch_add(1, Ordering::Relaxed);
+            BYTES.fetch_add(size as u64, Ordering::Relaxed);
+            let live = LIVE.fetch_add(size as i64, Ordering::Relaxed) + size as i64;
+            PEAK.fetch_max(live, Ordering::Relaxed);
+        }
+    }
+
+    fn freed(size: usize) {
+        if ENABLED.load(Ordering::Relaxed) {
+            LIVE.fetch_sub(size as i64, Ordering::Relaxed);
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::freed(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            Self::freed(layout.size());
+            Self::allocated(new_size);
+        }
+        new_ptr
+    }
+}
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn reset() {
+    COUNT.store(0, Ordering::Relaxed);
+    BYTES.store(0, Ordering::Relaxed);
+    LIVE.store(0, Ordering::Relaxed);
+    PEAK.store(0, Ordering::Relaxed);
+}
+
+// Allocations since the last reset
+pub fn snapshot() -> Allocations {
+    Allocations {
+        count: COUNT.load(Ordering::Relaxed),
+        bytes: BYTES.load(Ordering::Relaxed),
+        peak: PEAK.load(Ordering::Relaxed).max(0) as u64,
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Allocations {
+    pub count: u64,
+    pub bytes: u64,
+    // Highest number of live bytes allocated during the run
+    pub peak: u64,
+}
+
+impl std::fmt::Display for Allocations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} allocs, {}, peak {}",
+            self.count,
+            ByteFormatter(self.bytes),
+            ByteFormatter(self.peak)
+        )
+    }
+}
+
+pub struct ByteFormatter(pub u64);
+
+impl std::fmt::Display for ByteFormatter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let bytes = self.0 as f64;
+        match self.0 {
+            n if n >= 1 << 30 => write!(f, "{:.1}GiB", bytes / (1u64 << 30) as f64),
+            n if n >= 1 << 20 => write!(f, "{:.1}MiB", bytes / (1u64 << 20) as f64),
+            n if n >= 1 << 10 => write!(f, "{:.1}KiB", bytes / (1u64 << 10) as f64),
+            n => write!(f, "{n}B"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn test_counting() {
+        crate::util::test::setup_tracing();
+
+        // Other tests allocate concurrently, so the counts are only bounded from below
+        enable();
+        reset();
+        let mut buffer = Vec::<u8>::with_capacity(4096);
+        buffer.extend_from_slice(&[1; 4096]);
+        let allocations = snapshot();
+        drop(buffer);
+
+        assert!(allocations.count >= 1, "{allocations:?}");
+        assert!(allocations.bytes >= 4096, "{allocations:?}");
+        assert!(allocations.peak >= 4096, "{allocations:?}");
+    }
+
+    #[rstest]
+    #[case(512, "512B")]
+    #[case(1536, "1.5KiB")]
+    #[case(3 << 20, "3.0MiB")]
+    fn test_byte_formatter(#[case] bytes: u64, #[case] expected: &str) {
+        crate::util::test::setup_tracing();
+        assert_eq!(ByteFormatter(bytes).to_string(), expected);
+    }
+}
@@ -1,23 +1,35 @@
-use super::{Error, Result};
+use super::{Error, Result, Solution};
 
-pub const INPUT_FILE: &str = "inputs/day01/input.txt";
+pub struct Day;
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part1(input)
-}
+impl Solution for Day {
+    const DAY: u32 = 1;
+    const INPUT_FILE: &'static str = "inputs/day01/input.txt";
 
-fn solve_part1(input: &str) -> Result<i64> {
-    let sum = parse(input)?.into_iter().map(|mass| (mass / 3) - 2).sum();
-    Ok(sum)
+    type Input<'a> = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part2(input)
+    }
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part2(input)
+fn solve_part1(masses: &[i64]) -> Result<i64> {
+    let sum = masses.iter().copied().map(|mass| (mass / 3) - 2).sum();
+    Ok(sum)
 }
 
-fn solve_part2(input: &str) -> Result<i64> {
-    let sum = parse(input)?
-        .into_iter()
+fn solve_part2(masses: &[i64]) -> Result<i64> {
+    let sum = masses
+        .iter()
+        .copied()
         .flat_map(|mut mass| {
             std::iter::from_fn(move || {
                 mass = mass / 3 - 2;
@@ -51,7 +63,7 @@ mod tests {
     #[case("100756", 33583)]
     fn test_part1(#[case] input: &str, #[case] expected: i64) -> Result<()> {
         crate::util::test::setup_tracing();
-        let result = solve_part1(input)?;
+        let result = solve_part1(&parse(input)?)?;
         assert_eq!(result, expected);
         Ok(())
     }
@@ -63,7 +75,7 @@ mod tests {
     #[case("100756", 50346)]
     fn test_part2(#[case] input: &str, #[case] expected: i64) -> Result<()> {
         crate::util::test::setup_tracing();
-        let result = solve_part2(input)?;
+        let result = solve_part2(&parse(input)?)?;
         assert_eq!(result, expected);
         Ok(())
     }
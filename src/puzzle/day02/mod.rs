@@ -1,45 +1,51 @@
-use itertools::Itertools;
-use rayon::prelude::*;
-
 use super::{
-    intcode::{self, Intcode},
-    Error, Result,
+    intcode::{
+        self,
+        symbolic::{Symbolic, Target},
+        Intcode,
+    },
+    Error, Result, Solution,
 };
 
-pub const INPUT_FILE: &str = "inputs/day02/input.txt";
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 2;
+    const INPUT_FILE: &'static str = "inputs/day02/input.txt";
+
+    type Input<'a> = Box<[i64]>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(intcode::parse_program(input)?)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part1(input)
+    }
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part1(input)
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part2(input)
+    }
 }
 
-fn solve_part1(input: &str) -> Result<i64> {
-    let mut program = intcode::parse_program(input)?;
+fn solve_part1(program: &[i64]) -> Result<i64> {
+    let mut program = program.to_vec();
     program[1] = 12;
     program[2] = 2;
     let result = run(program)?;
     Ok(result)
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part2(input)
-}
-
-fn solve_part2(input: &str) -> Result<i64> {
-    let original_program = intcode::parse_program(input)?;
+fn solve_part2(program: &[i64]) -> Result<i64> {
+    let mut symbolic = Symbolic::new(program);
+    let noun = symbolic.cell(1, 0..=99);
+    let verb = symbolic.cell(2, 0..=99);
 
-    let (a, b, _) = (0..=99)
-        .cartesian_product(0..=99)
-        .par_bridge()
-        .flat_map(|(a, b)| {
-            let mut program = original_program.clone();
-            program[1] = a;
-            program[2] = b;
-            run(program).map(|result| (a, b, result))
-        })
-        .find_any(|&(_, _, result)| result == 19690720)
+    let assignment = symbolic
+        .solve(Target::Memory(0), 19690720)?
         .ok_or(Error::search("values not found"))?;
 
-    Ok(100 * a + b)
+    Ok(100 * assignment[&noun] + assignment[&verb])
 }
 
 fn run(program: impl AsRef<[i64]>) -> intcode::Result<i64> {
@@ -53,20 +59,27 @@ mod tests {
     use super::*;
     use rstest::*;
 
-    fn input(which: usize) -> Result<String> {
+    fn program(which: usize) -> Result<Box<[i64]>> {
         let file = format!("inputs/day02/test.{}.txt", which);
-        let file = std::fs::read_to_string(file)?;
-        Ok(file)
+        Ok(intcode::format::load_file(file)?)
     }
 
     #[rstest]
     #[case(0, 3500)]
     fn test_part1(#[case] which: usize, #[case] expected: i64) -> Result<()> {
         crate::util::test::setup_tracing();
-        let input = input(which)?;
-        let program = intcode::parse_program(&input)?;
+        let program = program(which)?;
         let result = run(program)?;
         assert_eq!(result, expected);
         Ok(())
     }
+
+    #[rstest]
+    #[case("1,0,0,3,1002,1,200000,19,1,19,2,19,1001,19,90700,0,99,0,0,0", 9820)]
+    fn test_part2(#[case] input: &str, #[case] expected: i64) -> Result<()> {
+        crate::util::test::setup_tracing();
+        let result = solve_part2(&intcode::parse_program(input)?)?;
+        assert_eq!(result, expected);
+        Ok(())
+    }
 }
@@ -3,7 +3,7 @@ use num::Zero;
 
 mod parser;
 
-use super::{Error, Result};
+use super::{Error, Example, Result, Solution};
 
 use crate::util::{
     geometry::manhattan_distance,
@@ -11,18 +11,41 @@ use crate::util::{
     position::{Orientation, Position},
 };
 
-pub const INPUT_FILE: &str = "inputs/day03/input.txt";
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 3;
+    const INPUT_FILE: &'static str = "inputs/day03/input.txt";
+    const EXAMPLES: &'static [Example] = &[
+        Example::new(1, 0, "159"),
+        Example::new(1, 1, "135"),
+        Example::new(1, 2, "6"),
+        Example::new(2, 0, "610"),
+        Example::new(2, 1, "410"),
+        Example::new(2, 2, "30"),
+    ];
+
+    type Input<'a> = Vec<Vec<(Position, i64)>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        let paths = input.lines().map(path).collect::<Result<Vec<_>>>()?;
+        if paths.len() < 2 {
+            return Err(Error::input("should have 2 paths"));
+        }
+
+        Ok(paths)
+    }
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part1(input)
-}
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part1(input)
+    }
 
-fn solve_part1(input: &str) -> Result<i64> {
-    let paths = input.lines().map(path).collect::<Result<Vec<_>>>()?;
-    if paths.len() < 2 {
-        return Err(Error::input("should have 2 paths"));
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part2(input)
     }
+}
 
+fn solve_part1(paths: &[Vec<(Position, i64)>]) -> Result<i64> {
     let a_iter = paths[0]
         .iter()
         .map(|(p, _)| *p)
@@ -42,16 +65,7 @@ fn solve_part1(input: &str) -> Result<i64> {
     Ok(result)
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part2(input)
-}
-
-fn solve_part2(input: &str) -> Result<i64> {
-    let paths = input.lines().map(path).collect::<Result<Vec<_>>>()?;
-    if paths.len() < 2 {
-        return Err(Error::input("should have 2 paths"));
-    }
-
+fn solve_part2(paths: &[Vec<(Position, i64)>]) -> Result<i64> {
     let a_iter = paths[0]
         .iter()
         .tuple_windows()
@@ -149,7 +163,7 @@ mod tests {
     fn test_part1(#[case] which: usize, #[case] expected: i64) -> Result<()> {
         crate::util::test::setup_tracing();
         let input = input(which)?;
-        let result = solve_part1(&input)?;
+        let result = solve_part1(&Day::parse(&input)?)?;
         assert_eq!(result, expected);
         Ok(())
     }
@@ -161,7 +175,7 @@ mod tests {
     fn test_part2(#[case] which: usize, #[case] expected: i64) -> Result<()> {
         crate::util::test::setup_tracing();
         let input = input(which)?;
-        let result = solve_part2(&input)?;
+        let result = solve_part2(&Day::parse(&input)?)?;
         assert_eq!(result, expected);
         Ok(())
     }
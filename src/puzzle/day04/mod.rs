@@ -1,16 +1,29 @@
 use itertools::Itertools;
 
-use super::{Error, Result};
+use super::{Error, Result, Solution};
 
-pub const INPUT_FILE: &str = "inputs/day04/input.txt";
+pub struct Day;
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part1(input)
-}
+impl Solution for Day {
+    const DAY: u32 = 4;
+    const INPUT_FILE: &'static str = "inputs/day04/input.txt";
+
+    type Input<'a> = (u64, u64);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse(input)
+    }
 
-fn solve_part1(input: &str) -> Result<usize> {
-    let (start, end) = parse(input)?;
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part1(*input)
+    }
 
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part2(*input)
+    }
+}
+
+fn solve_part1((start, end): (u64, u64)) -> Result<usize> {
     let start_digits = Digits::<6>::from(start);
     let candidate_iter = std::iter::successors(Some(start_digits), |digits| {
         Some(next_candidate_password(*digits))
@@ -26,13 +39,7 @@ fn solve_part1(input: &str) -> Result<usize> {
     Ok(result)
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part2(input)
-}
-
-fn solve_part2(input: &str) -> Result<usize> {
-    let (start, end) = parse(input)?;
-
+fn solve_part2((start, end): (u64, u64)) -> Result<usize> {
     let start_digits = Digits::<6>::from(start);
     let candidate_iter = std::iter::successors(Some(start_digits), |digits| {
         Some(next_candidate_password(*digits))
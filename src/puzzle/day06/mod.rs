@@ -1,19 +1,37 @@
 use ahash::AHashMap as HashMap;
 
-use super::{Error, Result};
+use super::{Error, Example, Result, Solution};
 
 use crate::util::graph::search::breadth_first_search;
 
-pub const INPUT_FILE: &str = "inputs/day06/input.txt";
+const ROOT: &str = "COM";
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part1(input)
-}
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 6;
+    const INPUT_FILE: &'static str = "inputs/day06/input.txt";
+    const EXAMPLES: &'static [Example] = &[
+        Example::new(1, 0, "42"),
+        Example::new(2, 1, "4"),
+    ];
+
+    type Input<'a> = Tree<'a>;
 
-fn solve_part1(input: &str) -> Result<i64> {
-    let root = "COM";
-    let system = parse(input, root)?;
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse(input, ROOT)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part1(input)
+    }
 
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part2(input)
+    }
+}
+
+fn solve_part1(system: &Tree) -> Result<i64> {
     fn count(idx: usize, depth: i64, system: &Tree) -> i64 {
         if system.children[idx].is_empty() {
             depth
@@ -26,18 +44,12 @@ fn solve_part1(input: &str) -> Result<i64> {
         }
     }
 
-    let root_idx = system.index[root];
-    let orbit_count = count(root_idx, 0, &system);
+    let root_idx = system.index[ROOT];
+    let orbit_count = count(root_idx, 0, system);
     Ok(orbit_count)
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part2(input)
-}
-
-fn solve_part2(input: &str) -> Result<i64> {
-    let root = "COM";
-    let system = parse(input, root)?;
+fn solve_part2(system: &Tree) -> Result<i64> {
     let you_idx = system.index["YOU"];
     let san_idx = system.index["SAN"];
     let start =
@@ -61,7 +73,7 @@ fn solve_part2(input: &str) -> Result<i64> {
 }
 
 #[derive(Debug, Default, Clone)]
-struct Tree<'a> {
+pub struct Tree<'a> {
     nodes: Vec<&'a str>,
     index: HashMap<&'a str, usize>,
     children: Vec<Vec<usize>>,
@@ -133,7 +145,7 @@ mod tests {
     fn test_part1(#[case] which: usize, #[case] expected: i64) -> Result<()> {
         crate::util::test::setup_tracing();
         let input = input(which)?;
-        let result = solve_part1(&input)?;
+        let result = solve_part1(&parse(&input, ROOT)?)?;
         assert_eq!(result, expected);
         Ok(())
     }
@@ -143,7 +155,7 @@ mod tests {
     fn test_part2(#[case] which: usize, #[case] expected: i64) -> Result<()> {
         crate::util::test::setup_tracing();
         let input = input(which)?;
-        let result = solve_part2(&input)?;
+        let result = solve_part2(&parse(&input, ROOT)?)?;
         assert_eq!(result, expected);
         Ok(())
     }
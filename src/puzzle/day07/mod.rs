@@ -3,39 +3,53 @@ use rayon::prelude::*;
 
 use super::{
     intcode::{self, Intcode},
-    Error, Result,
+    Error, Example, Result, Solution,
 };
 
-pub const INPUT_FILE: &str = "inputs/day07/input.txt";
+pub struct Day;
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part1(input)
-}
+impl Solution for Day {
+    const DAY: u32 = 7;
+    const INPUT_FILE: &'static str = "inputs/day07/input.txt";
+    const EXAMPLES: &'static [Example] = &[
+        Example::new(1, 0, "43210"),
+        Example::new(1, 1, "54321"),
+        Example::new(1, 2, "65210"),
+        Example::new(2, 3, "139629729"),
+        Example::new(2, 4, "18216"),
+    ];
+
+    type Input<'a> = Box<[i64]>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(intcode::parse_program(input)?)
+    }
 
-fn solve_part1(input: &str) -> Result<i64> {
-    let program = intcode::parse_program(input)?;
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part1(input)
+    }
 
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part2(input)
+    }
+}
+
+fn solve_part1(program: &[i64]) -> Result<i64> {
     let result = (0..=4)
         .permutations(5)
         .par_bridge()
-        .flat_map(|phase| amplify_once(0, &phase, &program))
+        .flat_map(|phase| amplify_once(0, &phase, program))
         .max()
         .ok_or_else(|| Error::search("no working phase settings"))?;
 
     Ok(result)
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part2(input)
-}
-
-fn solve_part2(input: &str) -> Result<i64> {
-    let program = intcode::parse_program(input)?;
-
+fn solve_part2(program: &[i64]) -> Result<i64> {
     let result = (5..=9)
         .permutations(5)
         .par_bridge()
-        .flat_map(|phase| amplify(0, &phase, &program))
+        .flat_map(|phase| amplify(0, &phase, program))
         .max()
         .ok_or_else(|| Error::search("no working phase settings"))?;
 
@@ -85,10 +99,9 @@ mod tests {
     use super::*;
     use rstest::*;
 
-    fn input(which: usize) -> Result<String> {
+    fn program(which: usize) -> Result<Box<[i64]>> {
         let file = format!("inputs/day07/test.{}.txt", which);
-        let file = std::fs::read_to_string(file)?;
-        Ok(file)
+        Ok(intcode::format::load_file(file)?)
     }
 
     #[rstest]
@@ -97,8 +110,7 @@ mod tests {
     #[case(2, 65210)]
     fn test_part1(#[case] which: usize, #[case] expected: i64) -> Result<()> {
         crate::util::test::setup_tracing();
-        let input = input(which)?;
-        let result = solve_part1(&input)?;
+        let result = solve_part1(&program(which)?)?;
         assert_eq!(result, expected);
         Ok(())
     }
@@ -108,8 +120,7 @@ mod tests {
     #[case(4, 18216)]
     fn test_part2(#[case] which: usize, #[case] expected: i64) -> Result<()> {
         crate::util::test::setup_tracing();
-        let input = input(which)?;
-        let result = solve_part2(&input)?;
+        let result = solve_part2(&program(which)?)?;
         assert_eq!(result, expected);
         Ok(())
     }
@@ -1,17 +1,38 @@
 use itertools::Itertools;
 
 use crate::util::{
-    bitmap::{Bitmap, BoxDisplay},
+    bitmap::{Bitmap, BoxDisplay, BoxFormatter},
     display::OnNewLine,
     position::Position,
 };
 
-use super::{Error, Result};
+use super::{Error, Frames, Result, Solution};
 
-pub const INPUT_FILE: &str = "inputs/day08/input.txt";
+pub struct Day;
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part1(input, 25, 6)
+impl Solution for Day {
+    const DAY: u32 = 8;
+    const INPUT_FILE: &'static str = "inputs/day08/input.txt";
+
+    type Input<'a> = &'a str;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part1(input, 25, 6)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        let result = solve_part2(input, 25, 6)?;
+        Ok(OnNewLine(BoxDisplay(result)))
+    }
+
+    // The image of part 2 forming as the layers are stacked from the back to the front
+    fn visualize(input: &Self::Input<'_>, frames: &mut dyn Frames) -> Result<()> {
+        stack_layers(input, 25, 6, frames)
+    }
 }
 
 fn solve_part1(input: &str, width: u64, height: u64) -> Result<i64> {
@@ -34,11 +55,6 @@ fn solve_part1(input: &str, width: u64, height: u64) -> Result<i64> {
     Ok(ones * twos)
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    let result = solve_part2(input, 25, 6)?;
-    Ok(OnNewLine(BoxDisplay(result)))
-}
-
 fn solve_part2(input: &str, width: u64, height: u64) -> Result<Bitmap> {
     let input = input.trim().as_bytes();
     let mut bitmap = Bitmap::new(width, height);
@@ -66,6 +82,26 @@ fn solve_part2(input: &str, width: u64, height: u64) -> Result<Bitmap> {
     Ok(bitmap)
 }
 
+fn stack_layers(input: &str, width: u64, height: u64, frames: &mut dyn Frames) -> Result<()> {
+    let mut bitmap = Bitmap::new(width, height);
+
+    for layer in input.trim().as_bytes().chunks((width * height) as usize).rev() {
+        for (idx, pixel) in layer.iter().enumerate() {
+            let idx = idx as u64;
+            let position = Position::new((idx % width) as i64, (idx / width) as i64);
+            match pixel {
+                b'0' => bitmap.put(&position, false),
+                b'1' => bitmap.put(&position, true),
+                _ => {}
+            }
+        }
+
+        frames.frame(&BoxFormatter(&bitmap))?;
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -87,4 +123,16 @@ mod tests {
         assert_eq!(result, expected);
         Ok(())
     }
+
+    #[rstest]
+    fn test_stack_layers() -> Result<()> {
+        crate::util::test::setup_tracing();
+        let mut frames = Vec::<String>::new();
+        stack_layers("0222112222120000", 2, 2, &mut frames)?;
+
+        let expected = BoxDisplay(solve_part2("0110", 2, 2)?).to_string();
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames.last(), Some(&expected));
+        Ok(())
+    }
 }
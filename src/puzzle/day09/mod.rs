@@ -1,26 +1,35 @@
 use super::{
     intcode::{self, Intcode},
-    Result,
+    Result, Solution,
 };
 
-pub const INPUT_FILE: &str = "inputs/day09/input.txt";
+pub struct Day;
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part1(input)
+impl Solution for Day {
+    const DAY: u32 = 9;
+    const INPUT_FILE: &'static str = "inputs/day09/input.txt";
+
+    type Input<'a> = Box<[i64]>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(intcode::parse_program(input)?)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part2(input)
+    }
 }
 
-fn solve_part1(input: &str) -> Result<i64> {
-    let program = intcode::parse_program(input)?;
+fn solve_part1(program: &[i64]) -> Result<i64> {
     let outputs = Intcode::run_program_with_inputs(program, [1])?;
     Ok(outputs[outputs.len() - 1])
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part2(input)
-}
-
-fn solve_part2(input: &str) -> Result<i64> {
-    let program = intcode::parse_program(input)?;
+fn solve_part2(program: &[i64]) -> Result<i64> {
     let outputs = Intcode::run_program_with_inputs(program, [2])?;
     Ok(outputs[outputs.len() - 1])
 }
@@ -5,20 +5,41 @@ use rayon::prelude::*;
 
 use crate::util::{geometry::manhattan_distance, position::Position};
 
-use super::{Error, Result};
+use super::{Error, Example, Result, Solution};
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 10;
+    const INPUT_FILE: &'static str = "inputs/day10/input.txt";
+    const EXAMPLES: &'static [Example] = &[
+        Example::new(1, 0, "8"),
+        Example::new(1, 1, "33"),
+        Example::new(1, 2, "35"),
+        Example::new(1, 3, "41"),
+        Example::new(1, 4, "210"),
+        Example::new(2, 4, "802"),
+    ];
+
+    type Input<'a> = Vec<Position>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse(input)
+    }
 
-pub const INPUT_FILE: &str = "inputs/day10/input.txt";
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part1(input)
+    }
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part1(input)
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part2(input)
+    }
 }
 
-fn solve_part1(input: &str) -> Result<usize> {
-    let asteroids = parse(input)?;
-
+fn solve_part1(asteroids: &[Position]) -> Result<usize> {
     let result = asteroids
         .par_iter()
-        .map(|&position| count_visible_asteroids(position, &asteroids))
+        .map(|&position| count_visible_asteroids(position, asteroids))
         .max()
         .unwrap_or(0);
     Ok(result)
@@ -33,16 +54,10 @@ fn count_visible_asteroids(position: Position, asteroids: &[Position]) -> usize
         .count()
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part2(input)
-}
-
-fn solve_part2(input: &str) -> Result<i64> {
-    let asteroids = parse(input)?;
-
+fn solve_part2(asteroids: &[Position]) -> Result<i64> {
     let index = asteroids
         .par_iter()
-        .map(|&position| index_asteroids_by_slope(position, &asteroids))
+        .map(|&position| index_asteroids_by_slope(position, asteroids))
         .max_by_key(|index| index.len())
         .ok_or_else(|| Error::input("no asteroids"))?;
 
@@ -201,7 +216,7 @@ mod tests {
     fn test_part1(#[case] which: usize, #[case] expected: usize) -> Result<()> {
         crate::util::test::setup_tracing();
         let input = input(which)?;
-        let result = solve_part1(&input)?;
+        let result = solve_part1(&parse(&input)?)?;
         assert_eq!(result, expected);
         Ok(())
     }
@@ -211,7 +226,7 @@ mod tests {
     fn test_part2(#[case] which: usize, #[case] expected: i64) -> Result<()> {
         crate::util::test::setup_tracing();
         let input = input(which)?;
-        let result = solve_part2(&input)?;
+        let result = solve_part2(&parse(&input)?)?;
         assert_eq!(result, expected);
         Ok(())
     }
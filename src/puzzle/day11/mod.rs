@@ -2,43 +2,64 @@ use ahash::AHashMap as HashMap;
 use itertools::Itertools;
 
 use crate::util::{
-    bitmap::{Bitmap, BoxDisplay},
+    bitmap::{Bitmap, BoxDisplay, BoxFormatter},
     display::OnNewLine,
     position::{pos, Direction, Position},
 };
 
 use super::{
-    intcode::{self, Intcode, State},
-    Error, Result,
+    intcode::{self, protocol::Protocol, Intcode, State},
+    Frames, Result, Solution,
 };
 
-pub const INPUT_FILE: &str = "inputs/day11/input.txt";
+pub struct Day;
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part1(input)
+impl Solution for Day {
+    const DAY: u32 = 11;
+    const INPUT_FILE: &'static str = "inputs/day11/input.txt";
+
+    type Input<'a> = Box<[i64]>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(intcode::parse_program(input)?)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part2(input).map(|bitmap| OnNewLine(BoxDisplay(bitmap)))
+    }
+
+    // The registration identifier of part 2 being painted panel by panel
+    fn visualize(input: &Self::Input<'_>, frames: &mut dyn Frames) -> Result<()> {
+        run_robot(input, 1, |panels| {
+            frames.frame(&BoxFormatter(&paint(panels)))
+        })?;
+        Ok(())
+    }
 }
 
-fn solve_part1(input: &str) -> Result<usize> {
-    let program = intcode::parse_program(input)?;
-    let panels = run_robot(program, 0)?;
+fn solve_part1(program: &[i64]) -> Result<usize> {
+    let panels = run_robot(program, 0, |_| Ok(()))?;
     Ok(panels.len())
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part2(input).map(|bitmap| OnNewLine(BoxDisplay(bitmap)))
+fn solve_part2(program: &[i64]) -> Result<Bitmap> {
+    let panels = run_robot(program, 1, |_| Ok(()))?;
+    Ok(paint(&panels))
 }
 
-fn solve_part2(input: &str) -> Result<Bitmap> {
-    let program = intcode::parse_program(input)?;
-    let panels = run_robot(program, 1)?;
-
+// The white panels, cropped to their bounding box
+fn paint(panels: &HashMap<Position, i64>) -> Bitmap {
     let (min, max) = {
         let mut white_panels =
             panels
                 .iter()
                 .filter_map(|(&position, &color)| if color != 0 { Some(position) } else { None });
         let Some(first) = white_panels.next() else {
-            return Ok(Bitmap::new(0, 0));
+            return Bitmap::new(0, 0);
         };
 
         white_panels.fold((first, first), |(min, max), next| {
@@ -60,10 +81,15 @@ fn solve_part2(input: &str) -> Result<Bitmap> {
         bitmap.put(&position, value);
     }
 
-    Ok(bitmap)
+    bitmap
 }
 
-fn run_robot(program: impl AsRef<[i64]>, starting_color: i64) -> Result<HashMap<Position, i64>> {
+// Runs the robot until it halts, calling `painted` with the panels after every panel it paints
+fn run_robot(
+    program: impl AsRef<[i64]>,
+    starting_color: i64,
+    mut painted: impl FnMut(&HashMap<Position, i64>) -> Result<()>,
+) -> Result<HashMap<Position, i64>> {
     let mut machine = Intcode::new(program);
 
     let mut position = Position::zeros();
@@ -75,17 +101,16 @@ fn run_robot(program: impl AsRef<[i64]>, starting_color: i64) -> Result<HashMap<
         let color = panels.get(&position).copied().unwrap_or(0);
         machine.push_input(color);
         machine.run()?;
-        let color = machine.pop_output().ok_or(intcode::Error::MissingOutput)?;
-        let turn = machine.pop_output().ok_or(intcode::Error::MissingOutput)?;
+        let (color, turn) = machine.expect_frame::<Robot>()?;
 
         // Paint panel
         panels.insert(position, color);
+        painted(&panels)?;
 
         // Move robot
         direction = match turn {
-            0 => direction.turn_left(),
-            1 => direction.turn_right(),
-            _ => return Err(Error::execution(&format!("unexpected turn {turn}"))),
+            Turn::Left => direction.turn_left(),
+            Turn::Right => direction.turn_right(),
         };
         position += direction;
 
@@ -96,3 +121,33 @@ fn run_robot(program: impl AsRef<[i64]>, starting_color: i64) -> Result<HashMap<
 
     Ok(panels)
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Turn {
+    Left,
+    Right,
+}
+
+struct Robot;
+
+impl Protocol for Robot {
+    const NAME: &'static str = "robot";
+    const ARITY: usize = 2;
+
+    type Frame = (i64, Turn);
+
+    fn decode(values: &[i64]) -> core::result::Result<Self::Frame, String> {
+        let color = match values[0] {
+            color @ (0 | 1) => color,
+            color => return Err(format!("unexpected color {color}")),
+        };
+
+        let turn = match values[1] {
+            0 => Turn::Left,
+            1 => Turn::Right,
+            turn => return Err(format!("unexpected turn {turn}")),
+        };
+
+        Ok((color, turn))
+    }
+}
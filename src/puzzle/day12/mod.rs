@@ -6,19 +6,39 @@ use crate::util::{
     vector::{vec3, Vec3},
 };
 
-use super::{Error, Result};
+use super::{Error, Example, Result, Solution};
 
-pub const INPUT_FILE: &str = "inputs/day12/input.txt";
+pub struct Day;
 
-mod parser;
+impl Solution for Day {
+    const DAY: u32 = 12;
+    const INPUT_FILE: &'static str = "inputs/day12/input.txt";
+    const EXAMPLES: &'static [Example] = &[
+        Example::new(2, 0, "2772"),
+        Example::new(2, 1, "4686774924"),
+    ];
+
+    type Input<'a> = Vec<Vec3>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(parser::parse(input)?)
+    }
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part1(input, 1000)
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part1(input, 1000)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part2(input)
+    }
 }
 
-fn solve_part1(input: &str, steps: u64) -> Result<u64> {
-    let mut moons = parser::parse(input)?
-        .into_iter()
+mod parser;
+
+fn solve_part1(moons: &[Vec3], steps: u64) -> Result<u64> {
+    let mut moons = moons
+        .iter()
+        .copied()
         .map(Body::new)
         .collect::<Box<[_]>>();
 
@@ -30,12 +50,7 @@ fn solve_part1(input: &str, steps: u64) -> Result<u64> {
     Ok(energy)
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part2(input)
-}
-
-fn solve_part2(input: &str) -> Result<u64> {
-    let initial_moons = parser::parse(input)?.into_boxed_slice();
+fn solve_part2(initial_moons: &[Vec3]) -> Result<u64> {
     let mut moons = initial_moons
         .iter()
         .copied()
@@ -48,7 +63,7 @@ fn solve_part2(input: &str) -> Result<u64> {
 
     let mut cycles = [0; 3];
     let mut step = 0;
-    while cycles.iter().any(|&step| step == 0) {
+    while cycles.contains(&0) {
         step_system(&mut moons);
         step += 1;
 
@@ -133,7 +148,7 @@ mod tests {
     fn test_part1(#[case] which: usize, #[case] steps: u64, #[case] expected: u64) -> Result<()> {
         crate::util::test::setup_tracing();
         let input = input(which)?;
-        let result = solve_part1(&input, steps)?;
+        let result = solve_part1(&parser::parse(&input)?, steps)?;
         assert_eq!(result, expected);
         Ok(())
     }
@@ -144,7 +159,7 @@ mod tests {
     fn test_part2(#[case] which: usize, #[case] expected: u64) -> Result<()> {
         crate::util::test::setup_tracing();
         let input = input(which)?;
-        let result = solve_part2(&input)?;
+        let result = solve_part2(&parser::parse(&input)?)?;
         assert_eq!(result, expected);
         Ok(())
     }
@@ -3,35 +3,52 @@ use itertools::Itertools;
 
 use crate::util::{
     bitmap::{Bitmap, BoxFormatter},
-    display::OnNewLine,
     position::{pos, Position},
     vector::vec2,
 };
 
 use super::{
-    intcode::{self, Intcode, State},
-    Error, Result,
+    intcode::{self, protocol::Protocol, Intcode, State},
+    Frames, Result, Solution,
 };
 
-pub const INPUT_FILE: &str = "inputs/day13/input.txt";
+pub struct Day;
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part1(input)
+impl Solution for Day {
+    const DAY: u32 = 13;
+    const INPUT_FILE: &'static str = "inputs/day13/input.txt";
+
+    type Input<'a> = Box<[i64]>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(intcode::parse_program(input)?)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part2(input)
+    }
+
+    // The game of part 2 being played
+    fn visualize(input: &Self::Input<'_>, frames: &mut dyn Frames) -> Result<()> {
+        let mut program = input.to_vec();
+        program[0] = 2;
+        let mut arcade = Arcade::new(program);
+        arcade.run_presenting(frames)
+    }
 }
 
-fn solve_part1(input: &str) -> Result<i64> {
-    let program = intcode::parse_program(input)?;
+fn solve_part1(program: &[i64]) -> Result<i64> {
     let mut arcade = Arcade::new(program);
     let _ = arcade.update()?;
     Ok(arcade.blocks)
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part2(input)
-}
-
-fn solve_part2(input: &str) -> Result<i64> {
-    let mut program = intcode::parse_program(input)?;
+fn solve_part2(program: &[i64]) -> Result<i64> {
+    let mut program = program.to_vec();
     program[0] = 2;
     let mut arcade = Arcade::new(program);
     arcade.run()?;
@@ -48,18 +65,34 @@ enum Tile {
     Ball,
 }
 
-impl TryFrom<i64> for Tile {
-    type Error = Error;
-
-    fn try_from(value: i64) -> core::result::Result<Self, Self::Error> {
-        match value {
-            0 => Ok(Self::Empty),
-            1 => Ok(Self::Wall),
-            2 => Ok(Self::Block),
-            3 => Ok(Self::Paddle),
-            4 => Ok(Self::Ball),
-            _ => Err(Error::execution(&format!("no tile of type {value}"))),
-        }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frame {
+    Score(i64),
+    Draw(Position, Tile),
+}
+
+struct Screen;
+
+impl Protocol for Screen {
+    const NAME: &'static str = "screen";
+    const ARITY: usize = 3;
+
+    type Frame = Frame;
+
+    fn decode(values: &[i64]) -> core::result::Result<Self::Frame, String> {
+        let tile = match values {
+            &[-1, 0, score] => return Ok(Frame::Score(score)),
+            &[x, y, _] if x < 0 || y < 0 => return Err(format!("no pixel at {x},{y}")),
+            [_, _, 0] => Tile::Empty,
+            [_, _, 1] => Tile::Wall,
+            [_, _, 2] => Tile::Block,
+            [_, _, 3] => Tile::Paddle,
+            [_, _, 4] => Tile::Ball,
+            [_, _, value] => return Err(format!("no tile of type {value}")),
+            _ => unreachable!("screen frames have three values"),
+        };
+
+        Ok(Frame::Draw(pos(values[0], values[1]), tile))
     }
 }
 
@@ -90,8 +123,6 @@ impl Arcade {
     }
 
     pub fn run(&mut self) -> Result<()> {
-        #![allow(dead_code)]
-
         loop {
             self.step()?;
 
@@ -103,14 +134,10 @@ impl Arcade {
         Ok(())
     }
 
-    pub fn run_presenting(&mut self) -> Result<()> {
-        #![allow(dead_code)]
-        use std::time::Duration;
-
+    pub fn run_presenting(&mut self, frames: &mut dyn Frames) -> Result<()> {
         loop {
             self.step()?;
-            self.present();
-            std::thread::sleep(Duration::from_millis(15));
+            self.present(frames)?;
 
             if self.blocks == 0 {
                 break;
@@ -134,16 +161,15 @@ impl Arcade {
     pub fn update(&mut self) -> Result<State> {
         self.machine.run()?;
 
-        for (position, value) in std::iter::from_fn(|| self.machine.pop_output())
-            .tuples()
-            .map(|(x, y, value)| (pos(x, y), value))
-        {
-            if position.x < 0 {
-                self.score = value;
-                continue;
-            }
+        for frame in self.machine.drain_frames::<Screen>()? {
+            let (position, tile) = match frame {
+                Frame::Score(score) => {
+                    self.score = score;
+                    continue;
+                }
+                Frame::Draw(position, tile) => (position, tile),
+            };
 
-            let tile = value.try_into()?;
             match tile {
                 Tile::Ball => {
                     self.ball = position.x;
@@ -172,9 +198,9 @@ impl Arcade {
         Ok(self.machine.get_state())
     }
 
-    fn present(&mut self) {
+    fn present(&mut self, frames: &mut dyn Frames) -> Result<()> {
         if self.screen.is_empty() {
-            return;
+            return Ok(());
         }
 
         if self.bitmap.is_none() {
@@ -197,6 +223,10 @@ impl Arcade {
             bitmap.put(&position, value);
         }
 
-        tracing::info!(score = self.score, screen = %OnNewLine(BoxFormatter(bitmap)));
+        frames.frame(&format_args!(
+            "Score: {}\n{}",
+            self.score,
+            BoxFormatter(bitmap)
+        ))
     }
 }
@@ -1,43 +1,62 @@
 use ahash::AHashMap as HashMap;
 
-use super::Result;
-
-pub const INPUT_FILE: &str = "inputs/day14/input.txt";
+use super::{Example, Result, Solution};
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 14;
+    const INPUT_FILE: &'static str = "inputs/day14/input.txt";
+    const EXAMPLES: &'static [Example] = &[
+        Example::new(1, 0, "31"),
+        Example::new(1, 1, "165"),
+        Example::new(1, 2, "13312"),
+        Example::new(1, 3, "180697"),
+        Example::new(1, 4, "2210736"),
+        Example::new(2, 2, "82892753"),
+        Example::new(2, 3, "5586022"),
+        Example::new(2, 4, "460664"),
+    ];
+
+    type Input<'a> = Reactions<'a>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(parser::parse(input)?)
+    }
 
-mod parser;
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part1(input)
+    }
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part1(input)
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part2(input)
+    }
 }
 
-fn solve_part1(input: &str) -> Result<u64> {
-    let reactions = parser::parse(input)?;
+mod parser;
+
+fn solve_part1(reactions: &Reactions) -> Result<u64> {
     let fuel = reactions.index["FUEL"];
     let ore = reactions.index["ORE"];
 
-    let counts = count_required_chemicals(fuel, 1, &reactions);
+    let counts = count_required_chemicals(fuel, 1, reactions);
 
     Ok(counts[ore].0)
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part2(input)
-}
-
-fn solve_part2(input: &str) -> Result<u64> {
-    let reactions = parser::parse(input)?;
+fn solve_part2(reactions: &Reactions) -> Result<u64> {
     let maximum_ore = 1_000_000_000_000u64;
     let fuel = reactions.index["FUEL"];
     let ore = reactions.index["ORE"];
 
-    let mut counts = count_required_chemicals(fuel, 1, &reactions);
+    let mut counts = count_required_chemicals(fuel, 1, reactions);
     let fuel_cost = counts[ore].0; // upper bound on the cost of fuel
     let mut fuel_amount = maximum_ore / fuel_cost; // lower bound on the amount of fuel
     let mut consumed_ore;
 
     loop {
         // Reuse the allocated memory from `counts`
-        count_required_chemicals_into(fuel, fuel_amount, &reactions, &mut counts);
+        count_required_chemicals_into(fuel, fuel_amount, reactions, &mut counts);
         consumed_ore = counts[ore].0;
 
         if consumed_ore > maximum_ore {
@@ -87,7 +106,7 @@ fn count_required_chemicals_into(
             if amount_required > counts[chemical].1 {
                 let new_required = amount_required - counts[chemical].1;
                 let reaction_count = new_required / reaction.amount_produced
-                    + if new_required % reaction.amount_produced != 0 {
+                    + if !new_required.is_multiple_of(reaction.amount_produced) {
                         1
                     } else {
                         0
@@ -181,7 +200,7 @@ mod tests {
     fn test_part1(#[case] which: usize, #[case] expected: u64) -> Result<()> {
         crate::util::test::setup_tracing();
         let input = input(which)?;
-        let result = solve_part1(&input)?;
+        let result = solve_part1(&parser::parse(&input)?)?;
         assert_eq!(result, expected);
         Ok(())
     }
@@ -193,7 +212,7 @@ mod tests {
     fn test_part2(#[case] which: usize, #[case] expected: u64) -> Result<()> {
         crate::util::test::setup_tracing();
         let input = input(which)?;
-        let result = solve_part2(&input)?;
+        let result = solve_part2(&parser::parse(&input)?)?;
         assert_eq!(result, expected);
         Ok(())
     }
@@ -10,7 +10,7 @@ use nom_supreme::final_parser::final_parser;
 
 use super::{Reaction, Reactions};
 
-pub fn parse(input: &str) -> Result<Reactions, nom::error::Error<&str>> {
+pub fn parse(input: &str) -> Result<Reactions<'_>, nom::error::Error<&str>> {
     let list = final_parser(parser)(input)?;
 
     let mut reactions = Reactions::default();
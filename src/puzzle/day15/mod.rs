@@ -1,20 +1,57 @@
-use ahash::AHashMap as HashMap;
+use ahash::{AHashMap as HashMap, AHashSet as HashSet};
 
-use crate::util::position::{Direction, Position};
+use crate::util::position::{pos, Direction, Position};
 
 use super::{
-    intcode::{self, Intcode},
-    Error, Result,
+    intcode::{self, protocol::Protocol, Intcode},
+    Error, Frames, Result, Solution,
 };
 
-pub const INPUT_FILE: &str = "inputs/day15/input.txt";
+pub struct Day;
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part1(input)
+impl Solution for Day {
+    const DAY: u32 = 15;
+    const INPUT_FILE: &'static str = "inputs/day15/input.txt";
+
+    type Input<'a> = Box<[i64]>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(intcode::parse_program(input)?)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part2(input)
+    }
+
+    // The oxygen of part 2 spreading through the explored map minute by minute
+    fn visualize(input: &Self::Input<'_>, frames: &mut dyn Frames) -> Result<()> {
+        let (map, oxygen_supply) = explore_map(input)?;
+
+        let mut filled = HashSet::from([oxygen_supply]);
+        let mut frontier = vec![oxygen_supply];
+        while !frontier.is_empty() {
+            frames.frame(&Picture {
+                map: &map,
+                filled: &filled,
+            })?;
+
+            frontier = frontier
+                .iter()
+                .flat_map(|&position| Direction::ALL.map(|direction| position + direction))
+                .filter(|neighbor| matches!(map.get(neighbor), Some(Tile::Empty)))
+                .filter(|&neighbor| filled.insert(neighbor))
+                .collect();
+        }
+
+        Ok(())
+    }
 }
 
-fn solve_part1(input: &str) -> Result<usize> {
-    let program = intcode::parse_program(input)?;
+fn solve_part1(program: &[i64]) -> Result<usize> {
     let path_length = find_path_length_to_oxygen_supply(program)?;
     Ok(path_length)
 }
@@ -34,17 +71,15 @@ fn find_path_length_to_oxygen_supply(program: impl AsRef<[i64]>) -> Result<usize
 
             machine.push_input(direction_input(direction));
             machine.run()?;
-            let out = machine.pop_output().ok_or(intcode::Error::MissingOutput)?;
-            match out {
-                0 => {
+            match machine.expect_frame::<Droid>()? {
+                Status::Wall => {
                     map.insert(position, Tile::Wall);
                     continue;
                 }
-                1 => {
+                Status::Moved => {
                     map.insert(position, Tile::Empty);
                 }
-                2 => return Ok(Some(path.len() + 1)),
-                _ => return Err(Error::execution(&format!("unexpected output {out}"))),
+                Status::Oxygen => return Ok(Some(path.len() + 1)),
             };
 
             path.push(direction);
@@ -55,7 +90,7 @@ fn find_path_length_to_oxygen_supply(program: impl AsRef<[i64]>) -> Result<usize
 
             machine.push_input(direction_input(direction.inverse()));
             machine.run()?;
-            machine.pop_output();
+            machine.expect_frame::<Droid>()?;
         }
 
         Ok(None)
@@ -70,12 +105,7 @@ fn find_path_length_to_oxygen_supply(program: impl AsRef<[i64]>) -> Result<usize
     path_length.ok_or_else(|| Error::search("no oxygen supply found"))
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part2(input)
-}
-
-fn solve_part2(input: &str) -> Result<usize> {
-    let program = intcode::parse_program(input)?;
+fn solve_part2(program: &[i64]) -> Result<usize> {
     let (map, oxygen_supply) = explore_map(program)?;
 
     let mut max_depth = 0;
@@ -114,20 +144,18 @@ fn explore_map(program: impl AsRef<[i64]>) -> Result<(HashMap<Position, Tile>, P
 
             machine.push_input(direction_input(direction));
             machine.run()?;
-            let out = machine.pop_output().ok_or(intcode::Error::MissingOutput)?;
-            match out {
-                0 => {
+            match machine.expect_frame::<Droid>()? {
+                Status::Wall => {
                     map.insert(position, Tile::Wall);
                     continue;
                 }
-                1 => {
+                Status::Moved => {
                     map.insert(position, Tile::Empty);
                 }
-                2 => {
+                Status::Oxygen => {
                     destination = Some(position);
                     map.insert(position, Tile::Empty);
                 }
-                _ => return Err(Error::execution(&format!("unexpected output {out}"))),
             };
 
             path.push(direction);
@@ -140,7 +168,7 @@ fn explore_map(program: impl AsRef<[i64]>) -> Result<(HashMap<Position, Tile>, P
 
             machine.push_input(direction_input(direction.inverse()));
             machine.run()?;
-            machine.pop_output();
+            machine.expect_frame::<Droid>()?;
         }
 
         Ok(destination)
@@ -157,6 +185,31 @@ fn explore_map(program: impl AsRef<[i64]>) -> Result<(HashMap<Position, Tile>, P
         .ok_or_else(|| Error::search("no oxygen supply found"))
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Wall,
+    Moved,
+    Oxygen,
+}
+
+struct Droid;
+
+impl Protocol for Droid {
+    const NAME: &'static str = "droid";
+    const ARITY: usize = 1;
+
+    type Frame = Status;
+
+    fn decode(values: &[i64]) -> core::result::Result<Self::Frame, String> {
+        match values[0] {
+            0 => Ok(Status::Wall),
+            1 => Ok(Status::Moved),
+            2 => Ok(Status::Oxygen),
+            status => Err(format!("unexpected status {status}")),
+        }
+    }
+}
+
 fn direction_input(direction: Direction) -> i64 {
     match direction {
         Direction::Up => 1,
@@ -171,3 +224,42 @@ enum Tile {
     Empty,
     Wall,
 }
+
+// The explored map with walls as `#` and the positions the oxygen has reached as `O`
+struct Picture<'a> {
+    map: &'a HashMap<Position, Tile>,
+    filled: &'a HashSet<Position>,
+}
+
+impl std::fmt::Display for Picture<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (min, max) = self.map.keys().fold(
+            (Position::zeros(), Position::zeros()),
+            |(min, max), next| {
+                (
+                    pos(min.x.min(next.x), min.y.min(next.y)),
+                    pos(max.x.max(next.x), max.y.max(next.y)),
+                )
+            },
+        );
+
+        for y in min.y..=max.y {
+            if y > min.y {
+                writeln!(f)?;
+            }
+
+            for x in min.x..=max.x {
+                let position = pos(x, y);
+                let c = match self.map.get(&position) {
+                    _ if self.filled.contains(&position) => 'O',
+                    Some(Tile::Wall) => '#',
+                    Some(Tile::Empty) => '.',
+                    None => ' ',
+                };
+                write!(f, "{c}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
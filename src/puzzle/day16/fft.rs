@@ -6,11 +6,7 @@ pub fn fft(signal: &[i64], destination: &mut [i64], len: usize, offset: usize) {
     let midpoint = len / 2;
     let buffer_delta = len - signal.len();
     let buffer_offset = offset - buffer_delta;
-    let buffer_midpoint = if buffer_delta > midpoint {
-        0
-    } else {
-        midpoint - buffer_delta
-    };
+    let buffer_midpoint = midpoint.saturating_sub(buffer_delta);
 
     if buffer_offset < buffer_midpoint {
         fft_full(
@@ -1,18 +1,44 @@
-use super::{Error, Result};
-
-pub const INPUT_FILE: &str = "inputs/day16/input.txt";
+use super::{Error, Example, Result, Solution};
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 16;
+    const INPUT_FILE: &'static str = "inputs/day16/input.txt";
+    const EXAMPLES: &'static [Example] = &[
+        Example::new(1, 1, "24176176"),
+        Example::new(1, 2, "73745418"),
+        Example::new(1, 3, "52432133"),
+        Example::new(2, 4, "84462026"),
+        Example::new(2, 5, "78725270"),
+        Example::new(2, 6, "53553731"),
+    ];
+
+    type Input<'a> = Box<[i64]>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        parse(input)
+    }
 
-mod fft;
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        let result = solve_part1(input, 100)?;
+        let bytes = Vec::from(result.map(|v| v as u8 + b'0'));
+        let string = String::from_utf8(bytes).expect("invalid utf8");
+        Ok(string)
+    }
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    let result = solve_part1(input, 100)?;
-    let bytes = Vec::from(result.map(|v| v as u8 + b'0'));
-    let string = String::from_utf8(bytes).expect("invalid utf8");
-    Ok(string)
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        let result = solve_part2(input, 100)?;
+        let bytes = Vec::from(result.map(|v| v as u8 + b'0'));
+        let string = String::from_utf8(bytes).expect("invalid utf8");
+        Ok(string)
+    }
 }
 
-fn solve_part1(input: &str, rounds: usize) -> Result<[i64; 8]> {
-    let mut signal = parse(input)?;
+mod fft;
+
+fn solve_part1(signal: &[i64], rounds: usize) -> Result<[i64; 8]> {
+    let mut signal = signal.to_vec();
     let mut next = signal.clone();
 
     for _ in 0..rounds {
@@ -25,15 +51,7 @@ fn solve_part1(input: &str, rounds: usize) -> Result<[i64; 8]> {
     Ok(result)
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    let result = solve_part2(input, 100)?;
-    let bytes = Vec::from(result.map(|v| v as u8 + b'0'));
-    let string = String::from_utf8(bytes).expect("invalid utf8");
-    Ok(string)
-}
-
-fn solve_part2(input: &str, rounds: usize) -> Result<[i64; 8]> {
-    let input = parse(input)?;
+fn solve_part2(input: &[i64], rounds: usize) -> Result<[i64; 8]> {
     let offset = {
         let mut rank = 1;
         let mut offset = 0;
@@ -104,7 +122,7 @@ mod tests {
     ) -> Result<()> {
         crate::util::test::setup_tracing();
         let input = input(which)?;
-        let result = solve_part1(&input, rounds)?;
+        let result = solve_part1(&parse(&input)?, rounds)?;
         assert_eq!(result, expected);
         Ok(())
     }
@@ -120,7 +138,7 @@ mod tests {
     ) -> Result<()> {
         crate::util::test::setup_tracing();
         let input = input(which)?;
-        let result = solve_part2(&input, rounds)?;
+        let result = solve_part2(&parse(&input)?, rounds)?;
         assert_eq!(result, expected);
         Ok(())
     }
@@ -1,21 +1,34 @@
 use ahash::AHashSet as HashSet;
-use itertools::Itertools;
 
 use crate::util::position::{pos, Direction, Position};
 
 use super::{
     intcode::{self, Intcode},
-    Error, Result,
+    Error, Result, Solution,
 };
 
-pub const INPUT_FILE: &str = "inputs/day17/input.txt";
+pub struct Day;
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part1(input)
+impl Solution for Day {
+    const DAY: u32 = 17;
+    const INPUT_FILE: &'static str = "inputs/day17/input.txt";
+
+    type Input<'a> = Box<[i64]>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(intcode::parse_program(input)?)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part2(input)
+    }
 }
 
-fn solve_part1(input: &str) -> Result<i64> {
-    let program = intcode::parse_program(input)?;
+fn solve_part1(program: &[i64]) -> Result<i64> {
     let output = Intcode::run_program_with_inputs(program, [])?;
     let screen = Screen::try_from(&output[..])?;
     let result = calibration(&screen);
@@ -36,18 +49,14 @@ fn calibration(screen: &Screen) -> i64 {
         .sum()
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part2(input)
-}
-
-fn solve_part2(input: &str) -> Result<i64> {
+fn solve_part2(program: &[i64]) -> Result<i64> {
     // Solved for my input with a "use eyes" algorithm
     let main_routine = "A,A,C,B,C,B,C,B,A,B\n";
     let a_program = "R,6,L,8,R,8\n";
     let b_program = "L,8,R,6,L,10,L,10\n";
     let c_program = "R,4,R,6,R,6,R,4,R,4\n";
 
-    let mut program = intcode::parse_program(input)?;
+    let mut program = program.to_vec();
     program[0] = 2;
     let mut machine = Intcode::new(program);
     machine.push_text_input(main_routine);
@@ -61,22 +70,7 @@ fn solve_part2(input: &str) -> Result<i64> {
     Ok(output[output.len() - 1])
 }
 
-fn printout(machine: &mut Intcode) {
-    #![allow(dead_code)]
-    let output = machine.drain_output();
-    let bytes = output.into_iter().map(|n| n as u8).collect_vec();
-    println!("{}", String::from_utf8(bytes).expect("utf8"));
-}
-
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
-struct Robot {
-    position: Position,
-    direction: Direction,
-    is_dead: bool,
-}
-
 struct Screen {
-    robot: Robot,
     scaffolding: HashSet<Position>,
 }
 
@@ -84,7 +78,6 @@ impl TryFrom<&[i64]> for Screen {
     type Error = Error;
 
     fn try_from(value: &[i64]) -> Result<Self> {
-        let mut robot = Robot::default();
         let mut scaffolding = HashSet::new();
 
         let (mut x, mut y) = (0, 0);
@@ -108,23 +101,10 @@ impl TryFrom<&[i64]> for Screen {
                 scaffolding.insert(pos(x, y));
             }
 
-            match byte {
-                b'X' => {
-                    robot.position = pos(x, y);
-                    robot.is_dead = true;
-                }
-                b'^' | b'v' | b'<' | b'>' => {
-                    robot.position = pos(x, y);
-                    robot.direction = dir(byte)?;
-                    robot.is_dead = false;
-                }
-                _ => {}
-            }
-
             x += 1;
         }
 
-        Ok(Self { robot, scaffolding })
+        Ok(Self { scaffolding })
     }
 }
 
@@ -142,16 +122,6 @@ impl TryFrom<&str> for Screen {
     }
 }
 
-fn dir(value: u8) -> Result<Direction> {
-    match value {
-        b'^' => Ok(Direction::Up),
-        b'v' => Ok(Direction::Down),
-        b'<' => Ok(Direction::Left),
-        b'>' => Ok(Direction::Right),
-        _ => Err(Error::parse(&format!("unknown direction value {value}"))),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,320 @@
+use std::path::{Path, PathBuf};
+
+// Programs are stored either as text, with values separated by commas and/or whitespace and
+// `#` starting a comment, or as a compact binary encoding: the `MAGIC` header followed by one
+// zigzag LEB128 varint per value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Comma,
+    Whitespace,
+    Binary,
+}
+
+const MAGIC: &[u8; 4] = b"ICB\x01";
+const VALUES_PER_LINE: usize = 16;
+
+pub fn load_file(path: impl AsRef<Path>) -> Result<Box<[i64]>> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path).map_err(|source| FormatError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    load(&bytes)
+}
+
+pub fn load(bytes: &[u8]) -> Result<Box<[i64]>> {
+    if bytes.starts_with(MAGIC) {
+        parse_binary(&bytes[MAGIC.len()..])
+    } else {
+        let text = std::str::from_utf8(bytes).map_err(|err| {
+            let (line, column) = text_position(&bytes[..err.valid_up_to()]);
+            FormatError::Text {
+                line,
+                column,
+                message: "invalid UTF-8".to_string(),
+            }
+        })?;
+        parse_text(text)
+    }
+}
+
+pub fn parse_text(input: &str) -> Result<Box<[i64]>> {
+    let mut program = Vec::new();
+    let mut expecting_value = true;
+    let mut last_comma = None;
+
+    for (line_idx, line) in input.lines().enumerate() {
+        let line_number = line_idx + 1;
+        let content = line.split('#').next().unwrap_or_default();
+
+        let mut chars = content.char_indices().peekable();
+        while let Some(&(start, c)) = chars.peek() {
+            let column = content[..start].chars().count() + 1;
+
+            if c.is_whitespace() {
+                chars.next();
+            } else if c == ',' {
+                if expecting_value {
+                    return Err(FormatError::text(line_number, column, "unexpected ','"));
+                }
+                expecting_value = true;
+                last_comma = Some((line_number, column));
+                chars.next();
+            } else {
+                let mut end = start;
+                while let Some(&(idx, c)) = chars.peek() {
+                    if c == ',' || c.is_whitespace() {
+                        break;
+                    }
+                    end = idx + c.len_utf8();
+                    chars.next();
+                }
+
+                let token = &content[start..end];
+                let value = token.parse::<i64>().map_err(|_| {
+                    FormatError::text(line_number, column, format!("invalid value {token:?}"))
+                })?;
+
+                program.push(value);
+                expecting_value = false;
+                last_comma = None;
+            }
+        }
+    }
+
+    if let Some((line, column)) = last_comma {
+        return Err(FormatError::text(line, column, "trailing ','"));
+    }
+
+    if program.is_empty() {
+        return Err(FormatError::text(1, 1, "empty program"));
+    }
+
+    Ok(program.into_boxed_slice())
+}
+
+fn parse_binary(bytes: &[u8]) -> Result<Box<[i64]>> {
+    let mut program = Vec::new();
+    let mut value: u64 = 0;
+    let mut shift = 0;
+
+    for (idx, &byte) in bytes.iter().enumerate() {
+        let offset = MAGIC.len() + idx;
+        let payload = (byte & 0x7f) as u64;
+        // The tenth byte of a varint only has room for the top bit
+        if shift == 63 && (payload > 1 || byte & 0x80 != 0) {
+            return Err(FormatError::binary(offset, "varint overflows 64 bits"));
+        }
+
+        value |= payload << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            program.push(((value >> 1) as i64) ^ -((value & 1) as i64));
+            value = 0;
+            shift = 0;
+        }
+    }
+
+    if shift != 0 {
+        return Err(FormatError::binary(
+            MAGIC.len() + bytes.len(),
+            "truncated varint",
+        ));
+    }
+
+    if program.is_empty() {
+        return Err(FormatError::binary(MAGIC.len(), "empty program"));
+    }
+
+    Ok(program.into_boxed_slice())
+}
+
+pub fn save_file(path: impl AsRef<Path>, program: &[i64], format: Format) -> std::io::Result<()> {
+    std::fs::write(path, write(program, format))
+}
+
+pub fn write(program: &[i64], format: Format) -> Vec<u8> {
+    match format {
+        Format::Comma => {
+            let mut text = program
+                .iter()
+                .map(|value| value.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            text.push('\n');
+            text.into_bytes()
+        }
+        Format::Whitespace => {
+            let mut text = String::new();
+            for line in program.chunks(VALUES_PER_LINE) {
+                let line = line
+                    .iter()
+                    .map(|value| value.to_string())
+                    .collect::<Vec<_>>();
+                text.push_str(&line.join(" "));
+                text.push('\n');
+            }
+            text.into_bytes()
+        }
+        Format::Binary => {
+            let mut bytes = MAGIC.to_vec();
+            for &value in program.iter() {
+                let mut zigzag = ((value << 1) ^ (value >> 63)) as u64;
+                loop {
+                    let byte = (zigzag & 0x7f) as u8;
+                    zigzag >>= 7;
+                    if zigzag == 0 {
+                        bytes.push(byte);
+                        break;
+                    }
+                    bytes.push(byte | 0x80);
+                }
+            }
+            bytes
+        }
+    }
+}
+
+fn text_position(bytes: &[u8]) -> (usize, usize) {
+    let text = String::from_utf8_lossy(bytes);
+    let line = text.matches('\n').count() + 1;
+    let column = text.rsplit('\n').next().unwrap_or_default().chars().count() + 1;
+    (line, column)
+}
+
+pub type Result<T> = core::result::Result<T, FormatError>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum FormatError {
+    #[error("Program error: line {line}, column {column}: {message}")]
+    Text {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    #[error("Program error: byte {offset}: {message}")]
+    Binary { offset: usize, message: String },
+    #[error("Program error: can't read {}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+impl FormatError {
+    fn text(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self::Text {
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    fn binary(offset: usize, message: impl Into<String>) -> Self {
+        Self::Binary {
+            offset,
+            message: message.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("1,2,3", &[1, 2, 3])]
+    #[case("1,2,3\n", &[1, 2, 3])]
+    #[case(" 1, 2 ,\n3 ", &[1, 2, 3])]
+    #[case("1 2\n-3\t4", &[1, 2, -3, 4])]
+    #[case("# header\n1,2 # first\n\n3 # last", &[1, 2, 3])]
+    fn test_parse_text(#[case] input: &str, #[case] expected: &[i64]) -> Result<()> {
+        crate::util::test::setup_tracing();
+        let result = parse_text(input)?;
+        assert_eq!(result.as_ref(), expected);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("1,2,x3", 1, 5)]
+    #[case("1,2\n  3,,4", 2, 5)]
+    #[case(",1", 1, 1)]
+    #[case("1,2,\n# done\n", 1, 4)]
+    #[case("# nothing", 1, 1)]
+    #[case("1,2\n3,9999999999999999999", 2, 3)]
+    fn test_parse_text_error(
+        #[case] input: &str,
+        #[case] expected_line: usize,
+        #[case] expected_column: usize,
+    ) {
+        crate::util::test::setup_tracing();
+        let result = parse_text(input);
+        let Err(FormatError::Text { line, column, .. }) = result else {
+            panic!("expected a text error, got {result:?}");
+        };
+        assert_eq!((line, column), (expected_line, expected_column));
+    }
+
+    #[rstest]
+    #[case(Format::Comma)]
+    #[case(Format::Whitespace)]
+    #[case(Format::Binary)]
+    fn test_roundtrip(#[case] format: Format) -> Result<()> {
+        crate::util::test::setup_tracing();
+
+        for seed in 0..20 {
+            let mut program = crate::puzzle::intcode::fuzz::generate(seed, 128).program;
+            program.extend([i64::MIN, i64::MAX, -1, 0, 1]);
+
+            let bytes = write(&program, format);
+            let result = load(&bytes)?;
+            assert_eq!(result.as_ref(), program.as_slice());
+        }
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn test_binary_is_compact() {
+        crate::util::test::setup_tracing();
+        let program = [1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50];
+        let bytes = write(&program, Format::Binary);
+        assert!(bytes.len() < write(&program, Format::Comma).len());
+    }
+
+    #[rstest]
+    #[case(&[0x02, 0xe8], 6, "truncated varint")]
+    #[case(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02], 13, "overflows")]
+    #[case(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x81, 0x00], 13, "overflows")]
+    fn test_binary_error(
+        #[case] values: &[u8],
+        #[case] expected_offset: usize,
+        #[case] expected: &str,
+    ) {
+        crate::util::test::setup_tracing();
+        let bytes = [MAGIC.as_slice(), values].concat();
+        let result = load(&bytes);
+        let Err(FormatError::Binary { offset, message }) = result else {
+            panic!("expected a binary error, got {result:?}");
+        };
+        assert_eq!(offset, expected_offset);
+        assert!(message.contains(expected), "{message}");
+    }
+
+    #[rstest]
+    fn test_load_file_missing() {
+        crate::util::test::setup_tracing();
+        let path = std::env::temp_dir().join(format!("program-{}.txt", std::process::id()));
+        let result = load_file(&path);
+        let Err(FormatError::Io {
+            path: error_path, ..
+        }) = result
+        else {
+            panic!("expected an IO error, got {result:?}");
+        };
+        assert_eq!(error_path, path);
+    }
+}
@@ -0,0 +1,683 @@
+use std::ops::Range;
+
+use itertools::Itertools;
+
+use super::{Instruction, Intcode, Memory, State};
+
+// Generated programs are laid out as code starting at 0, followed by fixed data regions.
+// Every block of generated code leaves the relative base unchanged, so the relative base
+// is known statically everywhere and all three addressing modes can reach any chosen cell.
+const DATA: i64 = 2048;
+const CONSTANTS: i64 = DATA;
+const CONSTANTS_LEN: i64 = 32;
+const TARGETS: i64 = CONSTANTS + CONSTANTS_LEN;
+const TARGETS_LEN: i64 = 256;
+const COUNTERS: i64 = TARGETS + TARGETS_LEN;
+const COUNTERS_LEN: i64 = 4;
+const SCRATCH: i64 = COUNTERS + COUNTERS_LEN;
+const SCRATCH_LEN: i64 = 32;
+const END: i64 = SCRATCH + SCRATCH_LEN;
+
+const SMALL: i64 = 8;
+const MAX_CODE_LEN: usize = 1024;
+
+// Generated programs terminate well within this many steps
+pub const MAX_STEPS: usize = 100_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Case {
+    pub program: Vec<i64>,
+    pub inputs: Vec<i64>,
+}
+
+impl std::fmt::Display for Case {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "program {}", self.program.iter().join(","))?;
+        write!(f, "send {}", self.inputs.iter().join(", "))
+    }
+}
+
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    pub fn between(&mut self, low: i64, high: i64) -> i64 {
+        low + (self.next_u64() % (high - low + 1) as u64) as i64
+    }
+
+    pub fn chance(&mut self, numerator: u64, denominator: u64) -> bool {
+        self.next_u64() % denominator < numerator
+    }
+}
+
+pub fn generate(seed: u64, size: usize) -> Case {
+    let mut generator = Generator::new(seed);
+    generator.block(size.min(MAX_CODE_LEN), 0);
+    generator.finish()
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Read {
+    Value(i64),
+    Cell(i64),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Patch {
+    Code(usize),
+    Target(usize),
+}
+
+struct Generator {
+    rng: Rng,
+    code: Vec<i64>,
+    constants: Vec<i64>,
+    targets: Vec<i64>,
+    relative_base: i64,
+    multiplier: usize,
+    input_count: usize,
+}
+
+impl Generator {
+    fn new(seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+        let constants = (0..CONSTANTS_LEN)
+            .map(|_| rng.between(-SMALL, SMALL))
+            .collect_vec();
+
+        Self {
+            rng,
+            code: Vec::new(),
+            constants,
+            targets: Vec::new(),
+            relative_base: 0,
+            multiplier: 1,
+            input_count: 0,
+        }
+    }
+
+    fn finish(mut self) -> Case {
+        self.code.push(99);
+        assert!(self.code.len() as i64 <= DATA);
+        assert!(self.targets.len() as i64 <= TARGETS_LEN);
+
+        let mut program = vec![0; END as usize];
+        program[..self.code.len()].copy_from_slice(&self.code);
+        place(&mut program, CONSTANTS, &self.constants);
+        place(&mut program, TARGETS, &self.targets);
+
+        let inputs = (0..self.input_count)
+            .map(|_| self.rng.between(-SMALL, SMALL))
+            .collect_vec();
+
+        Case { program, inputs }
+    }
+
+    fn block(&mut self, size: usize, depth: usize) {
+        let end = self.code.len() + size;
+        while self.code.len() < end && self.has_room() {
+            self.item(end - self.code.len(), depth);
+        }
+    }
+
+    fn has_room(&self) -> bool {
+        self.code.len() + 32 < MAX_CODE_LEN && (self.targets.len() as i64) + 2 < TARGETS_LEN
+    }
+
+    fn item(&mut self, budget: usize, depth: usize) {
+        let nested = budget > 16 && depth < 3;
+        match self.rng.below(if nested { 10 } else { 7 }) {
+            0 => {
+                let (a, b) = (self.read_any(), self.read_any());
+                let destination = self.scratch();
+                self.emit(1, &[a, b], Some(destination));
+            }
+            1 => {
+                // Multiplication only sees small operands, so repeated products cannot overflow
+                let (a, b) = (self.read_small(), self.read_small());
+                let destination = self.scratch();
+                self.emit(2, &[a, b], Some(destination));
+            }
+            2 => {
+                let destination = self.scratch();
+                self.input_count += self.multiplier;
+                self.emit(3, &[], Some(destination));
+            }
+            3 => {
+                let a = self.read_any();
+                self.emit(4, &[a], None);
+            }
+            4 => {
+                let (a, b) = (self.read_any(), self.read_any());
+                let destination = self.scratch();
+                self.emit(7, &[a, b], Some(destination));
+            }
+            5 => {
+                let (a, b) = (self.read_any(), self.read_any());
+                let destination = self.scratch();
+                self.emit(8, &[a, b], Some(destination));
+            }
+            6 => {
+                // Jumps to the next instruction either way
+                let condition = self.read_any();
+                let target = self.code.len() as i64 + 3;
+                let opcode = if self.rng.chance(1, 2) { 5 } else { 6 };
+                self.emit_jump(opcode, condition, target);
+            }
+            7 => self.relative_base_block(budget / 2, depth),
+            8 => self.skip_block(budget / 2, depth),
+            _ => self.loop_block(budget / 2, depth),
+        }
+    }
+
+    fn relative_base_block(&mut self, size: usize, depth: usize) {
+        let offset = self.rng.between(1, SMALL) * if self.rng.chance(1, 2) { 1 } else { -1 };
+
+        let read = self.read_value(offset);
+        self.emit(9, &[read], None);
+        self.relative_base += offset;
+
+        self.block(size, depth + 1);
+
+        let read = self.read_value(-offset);
+        self.emit(9, &[read], None);
+        self.relative_base -= offset;
+    }
+
+    fn skip_block(&mut self, size: usize, depth: usize) {
+        let condition = self.read_any();
+        let opcode = if self.rng.chance(1, 2) { 5 } else { 6 };
+        let patch = self.emit_forward_jump(opcode, condition);
+
+        self.block(size, depth + 1);
+
+        let target = self.code.len() as i64;
+        self.patch(patch, target);
+    }
+
+    fn loop_block(&mut self, size: usize, depth: usize) {
+        let counter = COUNTERS + depth as i64;
+        let iterations = self.rng.between(1, 4);
+
+        let (zero, count) = (self.read_value(0), self.read_value(iterations));
+        self.emit(1, &[zero, count], Some(counter));
+
+        let start = self.code.len() as i64;
+        let multiplier = self.multiplier;
+        self.multiplier *= iterations as usize;
+
+        self.block(size, depth + 1);
+
+        self.multiplier = multiplier;
+        let (current, decrement) = (Read::Cell(counter), self.read_value(-1));
+        self.emit(1, &[current, decrement], Some(counter));
+        self.emit_jump(5, Read::Cell(counter), start);
+    }
+
+    fn read_any(&mut self) -> Read {
+        match self.rng.below(5) {
+            0 => Read::Value(self.rng.between(-SMALL, SMALL)),
+            1 => Read::Cell(CONSTANTS + self.rng.between(0, CONSTANTS_LEN - 1)),
+            2 => Read::Cell(COUNTERS + self.rng.between(0, COUNTERS_LEN - 1)),
+            3 if !self.code.is_empty() => Read::Cell(self.rng.below(self.code.len()) as i64),
+            _ => Read::Cell(self.scratch()),
+        }
+    }
+
+    fn read_small(&mut self) -> Read {
+        if self.rng.chance(1, 2) {
+            Read::Value(self.rng.between(-SMALL, SMALL))
+        } else {
+            Read::Cell(CONSTANTS + self.rng.between(0, CONSTANTS_LEN - 1))
+        }
+    }
+
+    fn read_value(&mut self, value: i64) -> Read {
+        let cells = self
+            .constants
+            .iter()
+            .positions(|&constant| constant == value)
+            .collect_vec();
+
+        if cells.is_empty() || self.rng.chance(1, 3) {
+            Read::Value(value)
+        } else {
+            Read::Cell(CONSTANTS + cells[self.rng.below(cells.len())] as i64)
+        }
+    }
+
+    fn scratch(&mut self) -> i64 {
+        SCRATCH + self.rng.between(0, SCRATCH_LEN - 1)
+    }
+
+    fn encode_read(&mut self, read: Read) -> (i64, i64) {
+        match read {
+            Read::Value(value) => (1, value),
+            Read::Cell(address) => self.encode_address(address),
+        }
+    }
+
+    fn encode_address(&mut self, address: i64) -> (i64, i64) {
+        if self.rng.chance(1, 2) {
+            (0, address)
+        } else {
+            (2, address - self.relative_base)
+        }
+    }
+
+    fn emit(&mut self, opcode: i64, reads: &[Read], write: Option<i64>) -> usize {
+        let mut parameters = reads
+            .iter()
+            .map(|&read| self.encode_read(read))
+            .collect_vec();
+        if let Some(address) = write {
+            let parameter = self.encode_address(address);
+            parameters.push(parameter);
+        }
+
+        let instruction = parameters
+            .iter()
+            .zip([100, 1_000, 10_000])
+            .fold(opcode, |acc, (&(mode, _), factor)| acc + mode * factor);
+
+        let position = self.code.len();
+        self.code.push(instruction);
+        self.code
+            .extend(parameters.into_iter().map(|(_, parameter)| parameter));
+        position
+    }
+
+    fn emit_jump(&mut self, opcode: i64, condition: Read, target: i64) {
+        let patch = self.emit_forward_jump(opcode, condition);
+        self.patch(patch, target);
+    }
+
+    fn emit_forward_jump(&mut self, opcode: i64, condition: Read) -> Patch {
+        if self.rng.chance(1, 2) {
+            let position = self.emit(opcode, &[condition, Read::Value(0)], None);
+            Patch::Code(position + 2)
+        } else {
+            let cell = self.targets.len();
+            self.targets.push(0);
+            self.emit(
+                opcode,
+                &[condition, Read::Cell(TARGETS + cell as i64)],
+                None,
+            );
+            Patch::Target(cell)
+        }
+    }
+
+    fn patch(&mut self, patch: Patch, target: i64) {
+        match patch {
+            Patch::Code(position) => self.code[position] = target,
+            Patch::Target(cell) => self.targets[cell] = target,
+        }
+    }
+}
+
+fn place(program: &mut [i64], address: i64, values: &[i64]) {
+    let address = address as usize;
+    program[address..address + values.len()].copy_from_slice(values);
+}
+
+pub trait Interpreter {
+    fn push_input(&mut self, input: i64);
+    fn step(&mut self) -> super::Result<State>;
+    fn pop_output(&mut self) -> Option<i64>;
+    fn registers(&self) -> Registers;
+    fn memory(&self) -> &Memory;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Registers {
+    pub instruction_pointer: i64,
+    pub relative_base: i64,
+}
+
+impl Interpreter for Intcode {
+    fn push_input(&mut self, input: i64) {
+        Intcode::push_input(self, input);
+    }
+
+    fn step(&mut self) -> super::Result<State> {
+        Intcode::step(self)
+    }
+
+    fn pop_output(&mut self) -> Option<i64> {
+        Intcode::pop_output(self)
+    }
+
+    fn registers(&self) -> Registers {
+        Registers {
+            instruction_pointer: self.instruction_pointer,
+            relative_base: self.relative_base,
+        }
+    }
+
+    fn memory(&self) -> &Memory {
+        self.get_memory()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    pub step: usize,
+    pub reason: String,
+    pub case: Case,
+}
+
+impl std::fmt::Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "divergence at step {}: {}", self.step, self.reason)?;
+        write!(f, "{}", self.case)
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub struct Differential<A, B> {
+    left: Box<dyn Fn(&[i64]) -> A>,
+    right: Box<dyn Fn(&[i64]) -> B>,
+    max_steps: usize,
+}
+
+impl<A, B> Differential<A, B>
+where
+    A: Interpreter,
+    B: Interpreter,
+{
+    pub fn new(
+        left: impl Fn(&[i64]) -> A + 'static,
+        right: impl Fn(&[i64]) -> B + 'static,
+        max_steps: usize,
+    ) -> Self {
+        Self {
+            left: Box::new(left),
+            right: Box::new(right),
+            max_steps,
+        }
+    }
+
+    pub fn check(&self, case: &Case) -> Option<Divergence> {
+        let (step, reason) = self.compare(case)?;
+        let case = self.minimise(case.clone());
+        let (step, reason) = self.compare(&case).unwrap_or((step, reason));
+        Some(Divergence { step, reason, case })
+    }
+
+    // Checks the programs generated from `seeds`, returning the first seed on which the
+    // interpreters diverge along with the minimised reproducer
+    pub fn search(&self, seeds: Range<u64>, size: usize) -> Option<(u64, Divergence)> {
+        seeds
+            .into_iter()
+            .find_map(|seed| self.check(&generate(seed, size)).map(|found| (seed, found)))
+    }
+
+    fn compare(&self, case: &Case) -> Option<(usize, String)> {
+        let mut left = (self.left)(&case.program);
+        let mut right = (self.right)(&case.program);
+        for &input in case.inputs.iter() {
+            left.push_input(input);
+            right.push_input(input);
+        }
+
+        for step in 0..self.max_steps {
+            let (left_state, right_state) = (left.step(), right.step());
+
+            let reason = match (&left_state, &right_state) {
+                (Ok(a), Ok(b)) if a != b => Some(format!("state {a:?} != {b:?}")),
+                (Err(a), Err(b)) if a.to_string() != b.to_string() => {
+                    Some(format!("error {a} != {b}"))
+                }
+                (Ok(_), Err(err)) => Some(format!("only right failed: {err}")),
+                (Err(err), Ok(_)) => Some(format!("only left failed: {err}")),
+                _ => None,
+            }
+            .or_else(|| {
+                let (a, b) = (left.registers(), right.registers());
+                (a != b).then(|| format!("registers {a:?} != {b:?}"))
+            })
+            .or_else(|| {
+                let (a, b) = (left.pop_output(), right.pop_output());
+                (a != b).then(|| format!("output {a:?} != {b:?}"))
+            })
+            .or_else(|| (left.memory() != right.memory()).then(|| "memory differs".to_string()));
+
+            if reason.is_some() {
+                return reason.map(|reason| (step, reason));
+            }
+
+            if !matches!(left_state, Ok(State::Running)) {
+                return None;
+            }
+        }
+
+        None
+    }
+
+    // Greedy delta debugging: shrink the inputs and program while the divergence persists,
+    // neutralising ever smaller chunks of cells
+    fn minimise(&self, mut case: Case) -> Case {
+        let diverges = |case: &Case| self.compare(case).is_some();
+
+        while !case.inputs.is_empty() {
+            let mut candidate = case.clone();
+            candidate.inputs.pop();
+            if !diverges(&candidate) {
+                break;
+            }
+            case = candidate;
+        }
+
+        let mut chunk = case.program.len().next_power_of_two();
+        while chunk > 0 {
+            let mut start = 0;
+            while start < case.program.len() {
+                let end = (start + chunk).min(case.program.len());
+
+                let mut truncated = case.clone();
+                truncated.program.truncate(start);
+                truncated.program.extend(&case.program[end..]);
+                if end == case.program.len() && diverges(&truncated) {
+                    case = truncated;
+                    continue;
+                }
+
+                if case.program[start..end].iter().any(|&value| value != 0) {
+                    let mut zeroed = case.clone();
+                    zeroed.program[start..end].fill(0);
+                    if diverges(&zeroed) {
+                        case = zeroed;
+                    }
+                }
+
+                start = end;
+            }
+
+            chunk /= 2;
+        }
+
+        while case.program.last() == Some(&0) {
+            case.program.pop();
+        }
+
+        case
+    }
+}
+
+pub fn opcodes_and_modes(program: &[i64]) -> Vec<(u8, [u8; 3])> {
+    let mut position = 0;
+    let mut result = Vec::new();
+
+    while position < program.len() {
+        let Ok(instruction) = Instruction::decode(position as i64, program[position]) else {
+            break;
+        };
+
+        let parameters = match instruction.opcode {
+            1 | 2 | 7 | 8 => 3,
+            3 | 4 | 9 => 1,
+            5 | 6 => 2,
+            99 => 0,
+            _ => break,
+        };
+
+        let modes = instruction.parameter_modes.map(|mode| mode as u8);
+        result.push((instruction.opcode, modes));
+        position += 1 + parameters;
+
+        if instruction.opcode == 99 {
+            break;
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::intcode::lint::lint;
+    use rstest::*;
+
+    struct Faulty(Intcode);
+
+    impl Interpreter for Faulty {
+        fn push_input(&mut self, input: i64) {
+            self.0.push_input(input);
+        }
+
+        fn step(&mut self) -> crate::puzzle::intcode::Result<State> {
+            let opcode = self
+                .0
+                .get_memory()
+                .read(self.0.instruction_pointer as usize)
+                % 100;
+            let state = self.0.step()?;
+            if opcode == 9 {
+                self.0.relative_base += 1;
+            }
+            Ok(state)
+        }
+
+        fn pop_output(&mut self) -> Option<i64> {
+            self.0.pop_output()
+        }
+
+        fn registers(&self) -> Registers {
+            self.0.registers()
+        }
+
+        fn memory(&self) -> &Memory {
+            self.0.get_memory()
+        }
+    }
+
+    #[rstest]
+    fn test_generated_programs_terminate() {
+        crate::util::test::setup_tracing();
+
+        for seed in 0..200 {
+            let case = generate(seed, 256);
+            let mut machine = Intcode::new(&case.program);
+            for &input in case.inputs.iter() {
+                machine.push_input(input);
+            }
+
+            let diagnostics = lint(&case.program);
+            assert!(diagnostics.is_empty(), "seed {seed}: {diagnostics:?}");
+
+            let result = machine.run();
+            assert!(result.is_ok(), "seed {seed}: {result:?}\n{case}");
+            assert_eq!(
+                machine.get_state(),
+                State::Terminated,
+                "seed {seed}\n{case}"
+            );
+        }
+    }
+
+    #[rstest]
+    fn test_generated_programs_cover_instruction_set() {
+        crate::util::test::setup_tracing();
+
+        let mut opcodes = [false; 10];
+        let mut modes = [[false; 3]; 10];
+        for seed in 0..50 {
+            let case = generate(seed, 256);
+            for (opcode, parameter_modes) in opcodes_and_modes(&case.program) {
+                let opcode = if opcode == 99 { 0 } else { opcode as usize };
+                opcodes[opcode] = true;
+                for mode in parameter_modes {
+                    modes[opcode][mode as usize] = true;
+                }
+            }
+        }
+
+        assert!(opcodes.iter().all(|&seen| seen));
+        for opcode in [1, 2, 4, 5, 6, 7, 8, 9] {
+            assert_eq!(modes[opcode], [true; 3], "opcode {opcode}");
+        }
+    }
+
+    #[rstest]
+    fn test_identical_interpreters_agree() {
+        crate::util::test::setup_tracing();
+
+        let differential = Differential::new(
+            |program| Intcode::new(program),
+            |program| Intcode::new(program),
+            MAX_STEPS,
+        );
+        let divergence = differential.search(0..100, 256);
+        assert!(divergence.is_none(), "{divergence:?}");
+    }
+
+    #[rstest]
+    fn test_divergence_is_minimised() {
+        crate::util::test::setup_tracing();
+
+        let differential = Differential::new(
+            |program| Intcode::new(program),
+            |program| Faulty(Intcode::new(program)),
+            100_000,
+        );
+        let case = (0..)
+            .map(|seed| generate(seed, 256))
+            .find(|case| {
+                opcodes_and_modes(&case.program)
+                    .iter()
+                    .any(|&(opcode, _)| opcode == 9)
+            })
+            .unwrap();
+
+        let divergence = differential
+            .check(&case)
+            .expect("interpreters should diverge");
+        assert!(divergence.reason.starts_with("registers"), "{divergence}");
+        assert!(divergence.case.program.len() < case.program.len());
+        assert!(
+            divergence
+                .case
+                .program
+                .iter()
+                .filter(|&&value| value != 0)
+                .count()
+                <= 2
+        );
+    }
+}
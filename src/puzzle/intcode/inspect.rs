@@ -0,0 +1,286 @@
+use std::fmt::Write;
+
+use itertools::Itertools;
+
+use super::Memory;
+
+impl Memory {
+    fn sorted_pages(&self) -> Vec<(usize, &[i64; Self::PAGE_SIZE])> {
+        self.pages
+            .iter()
+            .map(|(&page_idx, page)| (page_idx, page.as_ref()))
+            .sorted_by_key(|&(page_idx, _)| page_idx)
+            .collect()
+    }
+
+    pub fn diff(&self, other: &Memory) -> MemoryDiff {
+        const EMPTY: [i64; Memory::PAGE_SIZE] = [0; Memory::PAGE_SIZE];
+
+        let page_indices = self
+            .pages
+            .keys()
+            .chain(other.pages.keys())
+            .copied()
+            .sorted()
+            .dedup();
+
+        let mut changes: Vec<Change> = Vec::new();
+        for page_idx in page_indices {
+            let old = self
+                .pages
+                .get(&page_idx)
+                .map_or(&EMPTY, |page| page.as_ref());
+            let new = other
+                .pages
+                .get(&page_idx)
+                .map_or(&EMPTY, |page| page.as_ref());
+            if old == new {
+                continue;
+            }
+
+            let base = page_idx << Self::PAGE_BITS;
+            for (offset, (&old, &new)) in old.iter().zip(new.iter()).enumerate() {
+                if old == new {
+                    continue;
+                }
+
+                let address = base + offset;
+                match changes.last_mut() {
+                    Some(change) if change.end() == address => {
+                        change.old.push(old);
+                        change.new.push(new);
+                    }
+                    _ => changes.push(Change {
+                        address,
+                        old: vec![old],
+                        new: vec![new],
+                    }),
+                }
+            }
+        }
+
+        MemoryDiff { changes }
+    }
+
+    pub fn dump(&self, radix: Radix) -> Dump<'_> {
+        Dump {
+            memory: self,
+            radix,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub address: usize,
+    pub old: Vec<i64>,
+    pub new: Vec<i64>,
+}
+
+impl Change {
+    pub fn end(&self) -> usize {
+        self.address + self.old.len()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryDiff {
+    changes: Vec<Change>,
+}
+
+impl MemoryDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+}
+
+impl std::fmt::Display for MemoryDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, change) in self.changes.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+
+            if change.old.len() == 1 {
+                write!(f, "{}: ", change.address)?;
+            } else {
+                write!(f, "{}..{}: ", change.address, change.end())?;
+            }
+
+            write!(
+                f,
+                "{} -> {}",
+                change.old.iter().join(","),
+                change.new.iter().join(",")
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Radix {
+    Decimal,
+    Hexadecimal,
+}
+
+// Dumps every allocated page as rows of cells followed by an ASCII column, with `*` marking
+// a gap of unallocated pages
+pub struct Dump<'a> {
+    memory: &'a Memory,
+    radix: Radix,
+}
+
+impl<'a> Dump<'a> {
+    const ROW_SIZE: usize = 16;
+
+    fn format_value(&self, value: i64) -> String {
+        match self.radix {
+            Radix::Decimal => format!("{value}"),
+            // Intcode values are signed, so negative ones keep their sign rather than showing
+            // as two's complement
+            Radix::Hexadecimal if value < 0 => format!("-{:x}", value.unsigned_abs()),
+            Radix::Hexadecimal => format!("{value:x}"),
+        }
+    }
+}
+
+impl<'a> std::fmt::Display for Dump<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pages = self.memory.sorted_pages();
+
+        let width = pages
+            .iter()
+            .flat_map(|(_, page)| page.iter())
+            .map(|&value| self.format_value(value).len())
+            .max()
+            .unwrap_or(1);
+
+        let mut previous = None;
+        for (page_idx, page) in pages {
+            if previous.is_some_and(|previous| previous + 1 != page_idx) {
+                writeln!(f, "*")?;
+            }
+            previous = Some(page_idx);
+
+            let base = page_idx << Memory::PAGE_BITS;
+            for (row_idx, row) in page.chunks(Self::ROW_SIZE).enumerate() {
+                let address = base + row_idx * Self::ROW_SIZE;
+                match self.radix {
+                    Radix::Decimal => write!(f, "{address:>8}:")?,
+                    Radix::Hexadecimal => write!(f, "{address:08x}:")?,
+                }
+
+                let mut ascii = String::with_capacity(Self::ROW_SIZE);
+                for &value in row.iter() {
+                    write!(f, " {:>width$}", self.format_value(value))?;
+
+                    let c = u8::try_from(value)
+                        .ok()
+                        .filter(|c| c.is_ascii_graphic() || *c == b' ')
+                        .map_or('.', char::from);
+                    ascii.write_char(c)?;
+                }
+
+                writeln!(f, "  |{ascii}|")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn test_diff() {
+        crate::util::test::setup_tracing();
+
+        let before = Memory::from([1, 2, 3, 4, 5]);
+        let mut after = before.clone();
+        after.write(1, 20);
+        after.write(2, 30);
+        after.write(4, -5);
+        after.write(1000, 7);
+
+        let diff = before.diff(&after);
+        assert_eq!(
+            diff.changes(),
+            &[
+                Change {
+                    address: 1,
+                    old: vec![2, 3],
+                    new: vec![20, 30],
+                },
+                Change {
+                    address: 4,
+                    old: vec![5],
+                    new: vec![-5],
+                },
+                Change {
+                    address: 1000,
+                    old: vec![0],
+                    new: vec![7],
+                },
+            ]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "1..3: 2,3 -> 20,30\n4: 5 -> -5\n1000: 0 -> 7"
+        );
+        assert!(after.diff(&after.clone()).is_empty());
+    }
+
+    #[rstest]
+    fn test_diff_ignores_zeroed_pages() {
+        crate::util::test::setup_tracing();
+
+        let mut memory = Memory::new();
+        memory.write(4096, 0);
+        assert!(memory.diff(&Memory::new()).is_empty());
+    }
+
+    #[rstest]
+    #[case(Radix::Decimal, "       0:  72 105   0   0")]
+    #[case(Radix::Hexadecimal, "00000000: 48 69  0  0")]
+    fn test_dump(#[case] radix: Radix, #[case] first_row: &str) {
+        crate::util::test::setup_tracing();
+
+        let mut memory = Memory::from([72, 105]);
+        memory.write(1024, 7);
+
+        let dump = memory.dump(radix).to_string();
+        let lines = dump.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 2 * Memory::PAGE_SIZE / 16 + 1);
+        assert!(lines[0].starts_with(first_row), "{}", lines[0]);
+        assert!(lines[0].ends_with("|Hi..............|"), "{}", lines[0]);
+        assert_eq!(lines[16], "*");
+    }
+
+    #[rstest]
+    #[case(
+        Radix::Decimal,
+        "       0:                   -1                   42 -9223372036854775808"
+    )]
+    #[case(
+        Radix::Hexadecimal,
+        "00000000:                -1                2a -8000000000000000"
+    )]
+    fn test_dump_negative(#[case] radix: Radix, #[case] first_row: &str) {
+        crate::util::test::setup_tracing();
+
+        let memory = Memory::from([-1, 42, i64::MIN]);
+        let dump = memory.dump(radix).to_string();
+        let first = dump.lines().next().unwrap_or_default();
+        assert!(first.starts_with(first_row), "{first}");
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use super::AddressingMode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Finding {
+    UnknownOpcode { opcode: i64 },
+    InvalidParameterMode { parameter: u8, mode: i64 },
+    ImmediateWrite { parameter: u8 },
+    JumpOutOfBounds { target: i64 },
+    RunsOffEnd,
+    UninitialisedRead { address: i64 },
+}
+
+impl Finding {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::UninitialisedRead { .. } => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+impl std::fmt::Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownOpcode { opcode } => write!(f, "unknown opcode {opcode}"),
+            Self::InvalidParameterMode { parameter, mode } => {
+                write!(f, "invalid mode {mode} for parameter {parameter}")
+            }
+            Self::ImmediateWrite { parameter } => {
+                write!(f, "parameter {parameter} is written to in immediate mode")
+            }
+            Self::JumpOutOfBounds { target } => write!(f, "jump to {target} outside the program"),
+            Self::RunsOffEnd => write!(f, "execution runs past the end of the program"),
+            Self::UninitialisedRead { address } => {
+                write!(f, "read of address {address}, which is never initialised")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub position: usize,
+    pub finding: Finding,
+}
+
+impl Diagnostic {
+    pub fn severity(&self) -> Severity {
+        self.finding.severity()
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity() {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{severity} @ {}: {}", self.position, self.finding)
+    }
+}
+
+// Follows control flow from address 0 through every jump with a statically known target.
+// Relative-mode accesses depend on the runtime relative base and are not checked.
+pub fn lint(program: &[i64]) -> Vec<Diagnostic> {
+    let mut instructions = Vec::new();
+    let mut diagnostics = Vec::new();
+
+    let mut visited = BTreeSet::new();
+    let mut queue = VecDeque::from([0]);
+    while let Some(position) = queue.pop_front() {
+        if !visited.insert(position) {
+            continue;
+        }
+
+        let mut report = |finding| diagnostics.push(Diagnostic { position, finding });
+
+        if position >= program.len() {
+            report(Finding::RunsOffEnd);
+            continue;
+        }
+
+        let Some(instruction) = decode(program, position, &mut report) else {
+            continue;
+        };
+
+        match instruction.opcode {
+            99 => {}
+            5 | 6 => {
+                let (jumps, falls_through) = match instruction.parameters[0] {
+                    (AddressingMode::Immediate, condition) => {
+                        let jumps = (condition != 0) == (instruction.opcode == 5);
+                        (jumps, !jumps)
+                    }
+                    _ => (true, true),
+                };
+
+                if falls_through {
+                    queue.push_back(position + 3);
+                }
+
+                if let Some(target) = instruction.constant_target(program).filter(|_| jumps) {
+                    if (0..program.len() as i64).contains(&target) {
+                        queue.push_back(target as usize);
+                    } else {
+                        report(Finding::JumpOutOfBounds { target });
+                    }
+                }
+            }
+            _ => queue.push_back(position + 1 + instruction.parameters.len()),
+        }
+
+        instructions.push(instruction);
+    }
+
+    // Only position-mode writes are known statically, so the check is limited to cells past
+    // the end of the program image that no reachable instruction writes to
+    let written = instructions
+        .iter()
+        .filter_map(|instruction| instruction.write())
+        .filter_map(|(mode, address)| (mode == AddressingMode::Position).then_some(address))
+        .collect::<BTreeSet<_>>();
+
+    for instruction in instructions.iter() {
+        for (mode, address) in instruction.reads() {
+            let uninitialised = mode == AddressingMode::Position
+                && address >= program.len() as i64
+                && !written.contains(&address);
+
+            if uninitialised {
+                diagnostics.push(Diagnostic {
+                    position: instruction.position,
+                    finding: Finding::UninitialisedRead { address },
+                });
+            }
+        }
+    }
+
+    diagnostics.sort_by_key(|diagnostic| diagnostic.position);
+    diagnostics
+}
+
+#[derive(Debug, Clone)]
+struct Decoded {
+    position: usize,
+    opcode: i64,
+    parameters: Vec<(AddressingMode, i64)>,
+}
+
+impl Decoded {
+    fn write(&self) -> Option<(AddressingMode, i64)> {
+        match self.opcode {
+            1 | 2 | 7 | 8 => Some(self.parameters[2]),
+            3 => Some(self.parameters[0]),
+            _ => None,
+        }
+    }
+
+    fn reads(&self) -> impl Iterator<Item = (AddressingMode, i64)> + '_ {
+        let count = match self.opcode {
+            1 | 2 | 5 | 6 | 7 | 8 => 2,
+            4 | 9 => 1,
+            _ => 0,
+        };
+        self.parameters[..count].iter().copied()
+    }
+
+    fn constant_target(&self, program: &[i64]) -> Option<i64> {
+        match self.parameters[1] {
+            (AddressingMode::Immediate, target) => Some(target),
+            (AddressingMode::Position, address) if (0..program.len() as i64).contains(&address) => {
+                Some(program[address as usize])
+            }
+            _ => None,
+        }
+    }
+}
+
+fn decode(program: &[i64], position: usize, report: &mut impl FnMut(Finding)) -> Option<Decoded> {
+    let instruction = program[position];
+    let opcode = instruction % 100;
+
+    let (parameter_count, write_parameter) = match opcode {
+        1 | 2 | 7 | 8 => (3, Some(2)),
+        3 => (1, Some(0)),
+        4 | 9 => (1, None),
+        5 | 6 => (2, None),
+        99 => (0, None),
+        _ => {
+            report(Finding::UnknownOpcode { opcode });
+            return None;
+        }
+    };
+
+    let mut parameters = Vec::with_capacity(parameter_count);
+    let mut valid = true;
+    for parameter in 0..parameter_count {
+        let mode = instruction / 10_i64.pow(parameter as u32 + 2) % 10;
+        let Ok(mode) = AddressingMode::decode(position as i64, parameter as u8, mode as u8) else {
+            report(Finding::InvalidParameterMode {
+                parameter: parameter as u8,
+                mode,
+            });
+            valid = false;
+            continue;
+        };
+
+        if mode == AddressingMode::Immediate && write_parameter == Some(parameter) {
+            report(Finding::ImmediateWrite {
+                parameter: parameter as u8,
+            });
+        }
+
+        let value = program.get(position + 1 + parameter).copied().unwrap_or(0);
+        parameters.push((mode, value));
+    }
+
+    valid.then_some(Decoded {
+        position,
+        opcode,
+        parameters,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case(&[1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50], &[])]
+    #[case(&[3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8], &[])]
+    #[case(&[1101, 1, 2, 0, 99], &[])]
+    #[case(&[11101, 1, 2, 0, 99], &[(0, Finding::ImmediateWrite { parameter: 2 })])]
+    #[case(&[104, 1, 42, 99], &[(2, Finding::UnknownOpcode { opcode: 42 })])]
+    #[case(&[304, 1, 99], &[(0, Finding::InvalidParameterMode { parameter: 0, mode: 3 })])]
+    #[case(&[1105, 1, 7, 99], &[(0, Finding::JumpOutOfBounds { target: 7 })])]
+    #[case(&[5, 0, 4, 99, -2], &[(0, Finding::JumpOutOfBounds { target: -2 })])]
+    #[case(&[104, 1], &[(2, Finding::RunsOffEnd)])]
+    #[case(&[1105, 1, 4, 98, 99], &[])]
+    #[case(&[1106, 1, 4, 98, 99], &[(3, Finding::UnknownOpcode { opcode: 98 })])]
+    #[case(&[4, 100, 99], &[(0, Finding::UninitialisedRead { address: 100 })])]
+    #[case(&[1101, 1, 2, 100, 4, 100, 99], &[])]
+    fn test_lint(#[case] program: &[i64], #[case] expected: &[(usize, Finding)]) {
+        crate::util::test::setup_tracing();
+        let mut result = lint(program)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.position, diagnostic.finding))
+            .collect::<Vec<_>>();
+        let mut expected = expected.to_vec();
+
+        let key = |&(position, finding): &(usize, Finding)| (position, finding.to_string());
+        result.sort_by_key(key);
+        expected.sort_by_key(key);
+        assert_eq!(result, expected);
+    }
+}
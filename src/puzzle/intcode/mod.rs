@@ -1,9 +1,22 @@
-use std::{collections::VecDeque, num::ParseIntError};
+use std::collections::VecDeque;
 
 use ahash::AHashMap as HashMap;
 
-pub fn parse_program(input: &str) -> core::result::Result<Box<[i64]>, ParseIntError> {
-    input.split(',').map(|s| s.trim().parse::<i64>()).collect()
+pub use format::FormatError;
+pub use inspect::{Change, Dump, MemoryDiff, Radix};
+
+pub mod format;
+
+pub mod fuzz;
+mod inspect;
+pub mod lint;
+pub mod protocol;
+#[cfg(test)]
+mod script;
+pub mod symbolic;
+
+pub fn parse_program(input: &str) -> format::Result<Box<[i64]>> {
+    format::parse_text(input)
 }
 
 #[derive(Debug, Clone)]
@@ -41,19 +54,16 @@ impl Intcode {
     }
 
     pub fn push_input(&mut self, input: i64) {
-        #![allow(dead_code)]
         self.input_buffer.push_back(input);
     }
 
     pub fn push_text_input(&mut self, input: impl AsRef<str>) {
-        #![allow(dead_code)]
         for input in input.as_ref().chars().map(|c| c as i64) {
             self.push_input(input);
         }
     }
 
     pub fn pop_output(&mut self) -> Option<i64> {
-        #![allow(dead_code)]
         self.output_buffer.pop_front()
     }
 
@@ -259,7 +269,7 @@ impl Intcode {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Memory {
     pages: HashMap<usize, Box<[i64; Self::PAGE_SIZE]>>,
 }
@@ -270,10 +280,7 @@ impl Memory {
     const PAGE_MASK: usize = usize::MAX >> (usize::BITS as usize - Self::PAGE_BITS);
 
     pub fn new() -> Self {
-        #![allow(dead_code)]
-        Self {
-            pages: HashMap::new(),
-        }
+        Self::default()
     }
 
     pub fn from_buffer(buffer: impl AsRef<[i64]>) -> Self {
@@ -313,6 +320,23 @@ impl Memory {
     }
 }
 
+impl PartialEq for Memory {
+    fn eq(&self, other: &Self) -> bool {
+        let pages_match = |a: &Self, b: &Self| {
+            a.pages
+                .iter()
+                .all(|(page_idx, page)| match b.pages.get(page_idx) {
+                    Some(other) => page == other,
+                    None => page.iter().all(|&value| value == 0),
+                })
+        };
+
+        pages_match(self, other) && pages_match(other, self)
+    }
+}
+
+impl Eq for Memory {}
+
 impl<T> From<T> for Memory
 where
     T: AsRef<[i64]>,
@@ -390,6 +414,19 @@ pub enum Error {
     },
     #[error("Intcode error: out of bounds memory access {address} @ {position}")]
     IllegalMemoryAccess { position: i64, address: i64 },
+    #[error(
+        "Intcode error: {protocol} protocol violation @ {position}: {message} (outputs {values:?})"
+    )]
+    Protocol {
+        protocol: &'static str,
+        position: i64,
+        values: Vec<i64>,
+        message: String,
+    },
+    #[error("Intcode error: symbolic execution exceeded {limit} paths")]
+    PathLimit { limit: usize },
+    #[error("Intcode error: execution exceeded {limit} steps")]
+    StepLimit { limit: usize },
 }
 
 #[cfg(test)]
@@ -398,10 +435,9 @@ mod tests {
     use crate::puzzle::Result;
     use rstest::*;
 
-    fn input(day: usize, which: usize) -> Result<String> {
+    fn program(day: usize, which: usize) -> Result<Box<[i64]>> {
         let file = format!("inputs/day{:02}/test.{}.txt", day, which);
-        let file = std::fs::read_to_string(file)?;
-        Ok(file)
+        Ok(format::load_file(file)?)
     }
 
     #[rstest]
@@ -421,9 +457,7 @@ mod tests {
         #[case] expected: i64,
     ) -> Result<()> {
         crate::util::test::setup_tracing();
-        let input = input(day, which)?;
-
-        let program = parse_program(&input)?;
+        let program = program(day, which)?;
         let result = Intcode::run_program_with_inputs(program, program_input)?;
 
         assert_eq!(result.len(), 1);
@@ -434,12 +468,48 @@ mod tests {
     #[rstest]
     fn test_quine() -> Result<()> {
         crate::util::test::setup_tracing();
-        let input = input(9, 0)?;
-
-        let program = parse_program(&input)?;
+        let program = program(9, 0)?;
         let result = Intcode::run_program_with_inputs(&program, [])?;
 
         assert_eq!(program.as_ref(), result.as_slice());
         Ok(())
     }
+
+    #[rstest]
+    fn test_scripts() -> Result<()> {
+        crate::util::test::setup_tracing();
+
+        let mut paths = std::fs::read_dir("fixtures/intcode")?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+        paths.retain(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "script")
+        });
+        paths.sort();
+
+        let failures = paths
+            .iter()
+            .flat_map(|path| {
+                let script = match script::Script::load(path) {
+                    Ok(script) => script,
+                    Err(err) => return vec![format!("{}: {err}", path.display())],
+                };
+
+                let diagnostics = script
+                    .programs()
+                    .flat_map(lint::lint)
+                    .filter(|diagnostic| diagnostic.severity() == lint::Severity::Error)
+                    .map(|diagnostic| format!("{}: {diagnostic}", path.display()));
+                let result = script.run().err();
+                let result = result.map(|err| format!("{}: {err}", path.display()));
+
+                diagnostics.chain(result).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        assert!(!paths.is_empty());
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+        Ok(())
+    }
 }
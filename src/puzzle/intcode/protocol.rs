@@ -0,0 +1,111 @@
+use super::{Error, Intcode, Result};
+
+// Describes how a machine's outputs are grouped into frames and decoded into typed values
+pub trait Protocol {
+    const NAME: &'static str;
+    const ARITY: usize;
+
+    type Frame;
+
+    fn decode(values: &[i64]) -> core::result::Result<Self::Frame, String>;
+}
+
+impl Intcode {
+    pub fn pop_frame<P: Protocol>(&mut self) -> Result<Option<P::Frame>> {
+        if self.output_buffer.is_empty() {
+            return Ok(None);
+        }
+
+        if self.output_buffer.len() < P::ARITY {
+            let values = Vec::from_iter(self.output_buffer.drain(..));
+            let message = format!("expected {} outputs, got {}", P::ARITY, values.len());
+            return Err(self.protocol_error::<P>(values, message));
+        }
+
+        let values = Vec::from_iter(self.output_buffer.drain(..P::ARITY));
+        match P::decode(&values) {
+            Ok(frame) => Ok(Some(frame)),
+            Err(message) => Err(self.protocol_error::<P>(values, message)),
+        }
+    }
+
+    pub fn expect_frame<P: Protocol>(&mut self) -> Result<P::Frame> {
+        match self.pop_frame::<P>()? {
+            Some(frame) => Ok(frame),
+            None => {
+                let message = format!("expected a frame, machine is {:?}", self.state);
+                Err(self.protocol_error::<P>(Vec::new(), message))
+            }
+        }
+    }
+
+    pub fn drain_frames<P: Protocol>(&mut self) -> Result<Vec<P::Frame>> {
+        std::iter::from_fn(|| self.pop_frame::<P>().transpose()).collect()
+    }
+
+    fn protocol_error<P: Protocol>(&self, values: Vec<i64>, message: String) -> Error {
+        Error::Protocol {
+            protocol: P::NAME,
+            position: self.instruction_pointer,
+            values,
+            message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Frame {
+        Point(i64, i64),
+        Origin,
+    }
+
+    struct Points;
+
+    impl Protocol for Points {
+        const NAME: &'static str = "points";
+        const ARITY: usize = 2;
+
+        type Frame = Frame;
+
+        fn decode(values: &[i64]) -> core::result::Result<Self::Frame, String> {
+            match values {
+                [0, 0] => Ok(Frame::Origin),
+                &[x, y] if x >= 0 && y >= 0 => Ok(Frame::Point(x, y)),
+                _ => Err("negative coordinates".to_string()),
+            }
+        }
+    }
+
+    #[rstest]
+    fn test_frames() -> Result<()> {
+        crate::util::test::setup_tracing();
+
+        let mut machine = Intcode::new([104, 1, 104, 2, 104, 0, 104, 0, 99]);
+        machine.run()?;
+        assert_eq!(
+            machine.drain_frames::<Points>()?,
+            vec![Frame::Point(1, 2), Frame::Origin]
+        );
+        assert!(machine.pop_frame::<Points>()?.is_none());
+        assert!(machine.expect_frame::<Points>().is_err());
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(&[104, 1, 99], "points protocol violation @ 2: expected 2 outputs, got 1 (outputs [1])")]
+    #[case(&[104, 1, 104, -1, 99], "points protocol violation @ 4: negative coordinates (outputs [1, -1])")]
+    fn test_violations(#[case] program: &[i64], #[case] expected: &str) -> Result<()> {
+        crate::util::test::setup_tracing();
+
+        let mut machine = Intcode::new(program);
+        machine.run()?;
+        let err = machine.drain_frames::<Points>().unwrap_err();
+        assert_eq!(err.to_string(), format!("Intcode error: {expected}"));
+        Ok(())
+    }
+}
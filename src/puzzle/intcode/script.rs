@@ -0,0 +1,273 @@
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
+
+use super::{format, Intcode, State};
+
+// Fixture scripts drive an Intcode machine line by line:
+//
+//   # comment
+//   program 3,9,8,9,10,9,4,9,99,-1,8   (or `load <path>`, relative to the script)
+//   send 8
+//   expect 1
+//   state terminated
+//   memory 9 = 1
+//   reset                              (starts a new run of the same program)
+//
+// `program` and `load` may appear again later on to start a run of another program.
+#[derive(Debug, Clone)]
+pub struct Script {
+    commands: Vec<(usize, Command)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Command {
+    Program(Box<[i64]>),
+    Send(Vec<i64>),
+    Expect(Vec<i64>),
+    State(State),
+    Memory(usize, Vec<i64>),
+    Reset,
+}
+
+impl Script {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path).map_err(|source| ScriptError::Io {
+            path: path.to_owned(),
+            source,
+        })?;
+
+        let directory = path.parent().unwrap_or(Path::new("."));
+
+        Self::parse(&source, directory)
+    }
+
+    pub fn parse(source: &str, directory: &Path) -> Result<Self> {
+        let mut commands = Vec::new();
+
+        for (idx, line) in source.lines().enumerate() {
+            let line_number = idx + 1;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (directive, arguments) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let arguments = arguments.trim();
+
+            let command = match directive {
+                "program" => {
+                    Command::Program(parse_values(line_number, arguments)?.into_boxed_slice())
+                }
+                "load" => {
+                    let program = format::load_file(directory.join(arguments))
+                        .map_err(|err| ScriptError::syntax(line_number, err.to_string()))?;
+                    Command::Program(program)
+                }
+                "send" => Command::Send(parse_values(line_number, arguments)?),
+                "expect" => Command::Expect(parse_values(line_number, arguments)?),
+                "state" => Command::State(parse_state(line_number, arguments)?),
+                "memory" => {
+                    let (address, values) = arguments.split_once('=').ok_or_else(|| {
+                        ScriptError::syntax(line_number, "expected `memory <address> = <values>`")
+                    })?;
+                    let address = address.trim().parse::<usize>().map_err(|_| {
+                        ScriptError::syntax(line_number, format!("invalid address {address:?}"))
+                    })?;
+                    Command::Memory(address, parse_values(line_number, values)?)
+                }
+                "reset" => Command::Reset,
+                _ => {
+                    return Err(ScriptError::syntax(
+                        line_number,
+                        format!("unknown directive {directive:?}"),
+                    ))
+                }
+            };
+
+            commands.push((line_number, command));
+        }
+
+        if !matches!(commands.first(), Some((_, Command::Program(_)))) {
+            let line = commands.first().map(|&(line, _)| line).unwrap_or(0);
+            return Err(ScriptError::syntax(
+                line,
+                "script must start with a program",
+            ));
+        }
+
+        Ok(Self { commands })
+    }
+
+    pub fn programs(&self) -> impl Iterator<Item = &[i64]> {
+        self.commands
+            .iter()
+            .filter_map(|(_, command)| match command {
+                Command::Program(program) => Some(program.as_ref()),
+                _ => None,
+            })
+    }
+
+    pub fn run(&self) -> Result<()> {
+        let mut program: &[i64] = &[];
+        let mut machine = Intcode::new(program);
+        let mut line = 0;
+
+        for (line_number, command) in self.commands.iter() {
+            line = *line_number;
+
+            match command {
+                Command::Program(new_program) => {
+                    check_drained(&mut machine, line)?;
+                    program = new_program;
+                    machine = Intcode::new(program);
+                }
+                Command::Send(values) => {
+                    for &value in values.iter() {
+                        machine.push_input(value);
+                    }
+                }
+                Command::Expect(expected) => {
+                    run(&mut machine, line)?;
+
+                    let mut actual = Vec::with_capacity(expected.len());
+                    while actual.len() < expected.len() {
+                        let Some(value) = machine.pop_output() else {
+                            break;
+                        };
+                        actual.push(value);
+                    }
+
+                    if actual != *expected {
+                        return Err(ScriptError::OutputMismatch {
+                            line,
+                            expected: expected.clone(),
+                            actual,
+                        });
+                    }
+                }
+                Command::State(expected) => {
+                    run(&mut machine, line)?;
+
+                    let actual = machine.get_state();
+                    if actual != *expected {
+                        return Err(ScriptError::StateMismatch {
+                            line,
+                            expected: *expected,
+                            actual,
+                        });
+                    }
+                }
+                Command::Memory(address, expected) => {
+                    run(&mut machine, line)?;
+
+                    let actual = (0..expected.len())
+                        .map(|offset| machine.get_memory().read(address + offset))
+                        .collect_vec();
+
+                    if actual != *expected {
+                        return Err(ScriptError::MemoryMismatch {
+                            line,
+                            address: *address,
+                            expected: expected.clone(),
+                            actual,
+                        });
+                    }
+                }
+                Command::Reset => {
+                    check_drained(&mut machine, line)?;
+                    machine = Intcode::new(program);
+                }
+            }
+        }
+
+        check_drained(&mut machine, line)
+    }
+}
+
+fn run(machine: &mut Intcode, line: usize) -> Result<()> {
+    machine
+        .run()
+        .map_err(|source| ScriptError::Intcode { line, source })
+}
+
+fn check_drained(machine: &mut Intcode, line: usize) -> Result<()> {
+    let remaining = machine.drain_output();
+    if remaining.is_empty() {
+        Ok(())
+    } else {
+        Err(ScriptError::UnexpectedOutput { line, remaining })
+    }
+}
+
+fn parse_values(line: usize, arguments: &str) -> Result<Vec<i64>> {
+    arguments
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            value
+                .parse::<i64>()
+                .map_err(|_| ScriptError::syntax(line, format!("invalid value {value:?}")))
+        })
+        .collect()
+}
+
+fn parse_state(line: usize, argument: &str) -> Result<State> {
+    match argument {
+        "waiting" => Ok(State::WaitingForInput),
+        "terminated" => Ok(State::Terminated),
+        _ => Err(ScriptError::syntax(
+            line,
+            format!("unknown state {argument:?}, expected `waiting` or `terminated`"),
+        )),
+    }
+}
+
+pub type Result<T> = core::result::Result<T, ScriptError>;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ScriptError {
+    #[error("Script error: line {line}: {message}")]
+    Syntax { line: usize, message: String },
+    #[error("Script error: cannot read {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Script error: line {line}: {source}")]
+    Intcode { line: usize, source: super::Error },
+    #[error("Script error: line {line}: expected output {expected:?}, got {actual:?}")]
+    OutputMismatch {
+        line: usize,
+        expected: Vec<i64>,
+        actual: Vec<i64>,
+    },
+    #[error("Script error: line {line}: expected state {expected:?}, got {actual:?}")]
+    StateMismatch {
+        line: usize,
+        expected: State,
+        actual: State,
+    },
+    #[error(
+        "Script error: line {line}: expected memory @ {address} to be {expected:?}, got {actual:?}"
+    )]
+    MemoryMismatch {
+        line: usize,
+        address: usize,
+        expected: Vec<i64>,
+        actual: Vec<i64>,
+    },
+    #[error("Script error: line {line}: unchecked output {remaining:?}")]
+    UnexpectedOutput { line: usize, remaining: Vec<i64> },
+}
+
+impl ScriptError {
+    fn syntax(line: usize, message: impl Into<String>) -> Self {
+        Self::Syntax {
+            line,
+            message: message.into(),
+        }
+    }
+}
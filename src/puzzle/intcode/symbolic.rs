@@ -0,0 +1,867 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    ops::RangeInclusive,
+};
+
+use ahash::AHashMap as HashMap;
+use itertools::Itertools;
+
+use super::{AddressingMode, Error, Instruction, Intcode, Result, State};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(usize);
+
+impl std::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "x{}", self.0)
+    }
+}
+
+pub type Assignment = BTreeMap<Symbol, i64>;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Polynomial {
+    // Monomials are sorted multisets of symbols, the empty monomial is the constant term
+    terms: BTreeMap<Vec<Symbol>, i64>,
+}
+
+impl Polynomial {
+    const MAX_DEGREE: usize = 8;
+    const MAX_TERMS: usize = 256;
+
+    pub fn constant(value: i64) -> Self {
+        let mut terms = BTreeMap::new();
+        if value != 0 {
+            terms.insert(Vec::new(), value);
+        }
+        Self { terms }
+    }
+
+    pub fn symbol(symbol: Symbol) -> Self {
+        Self {
+            terms: BTreeMap::from([(vec![symbol], 1)]),
+        }
+    }
+
+    pub fn as_constant(&self) -> Option<i64> {
+        match self.terms.len() {
+            0 => Some(0),
+            1 => self.terms.get(&Vec::new()).copied(),
+            _ => None,
+        }
+    }
+
+    pub fn symbols(&self) -> BTreeSet<Symbol> {
+        self.terms.keys().flatten().copied().collect()
+    }
+
+    fn checked_add(&self, other: &Self) -> Option<Self> {
+        let mut terms = self.terms.clone();
+        for (monomial, &coefficient) in other.terms.iter() {
+            let entry = terms.entry(monomial.clone()).or_insert(0);
+            *entry = entry.checked_add(coefficient)?;
+            if *entry == 0 {
+                terms.remove(monomial);
+            }
+        }
+
+        Self::bounded(terms)
+    }
+
+    fn checked_mul(&self, other: &Self) -> Option<Self> {
+        let mut terms: BTreeMap<Vec<Symbol>, i64> = BTreeMap::new();
+        for ((a, &x), (b, &y)) in self.terms.iter().cartesian_product(other.terms.iter()) {
+            let monomial = a.iter().merge(b.iter()).copied().collect_vec();
+            let entry = terms.entry(monomial.clone()).or_insert(0);
+            *entry = entry.checked_add(x.checked_mul(y)?)?;
+            if *entry == 0 {
+                terms.remove(&monomial);
+            }
+        }
+
+        Self::bounded(terms)
+    }
+
+    fn bounded(terms: BTreeMap<Vec<Symbol>, i64>) -> Option<Self> {
+        let degree = terms.keys().map(Vec::len).max().unwrap_or(0);
+        if degree > Self::MAX_DEGREE || terms.len() > Self::MAX_TERMS {
+            return None;
+        }
+
+        Some(Self { terms })
+    }
+
+    pub fn substitute(&self, assignment: &Assignment) -> Option<Self> {
+        let mut result = Self::default();
+        for (monomial, &coefficient) in self.terms.iter() {
+            let mut term = Self::constant(coefficient);
+            for symbol in monomial.iter() {
+                let factor = match assignment.get(symbol) {
+                    Some(&value) => Self::constant(value),
+                    None => Self::symbol(*symbol),
+                };
+                term = term.checked_mul(&factor)?;
+            }
+            result = result.checked_add(&term)?;
+        }
+
+        Some(result)
+    }
+
+    pub fn evaluate(&self, assignment: &Assignment) -> Option<i64> {
+        self.substitute(assignment)?.as_constant()
+    }
+
+    // Splits the polynomial into `coefficient * symbol + rest` if no monomial contains the
+    // symbol more than once
+    fn linear_in(&self, symbol: Symbol) -> Option<(Self, Self)> {
+        let mut coefficient = Self::default();
+        let mut rest = Self::default();
+
+        for (monomial, &value) in self.terms.iter() {
+            match monomial.iter().filter(|&&s| s == symbol).count() {
+                0 => {
+                    rest.terms.insert(monomial.clone(), value);
+                }
+                1 => {
+                    let reduced = monomial.iter().copied().filter(|&s| s != symbol).collect();
+                    coefficient.terms.insert(reduced, value);
+                }
+                _ => return None,
+            }
+        }
+
+        Some((coefficient, rest))
+    }
+}
+
+impl std::fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.terms.is_empty() {
+            return write!(f, "0");
+        }
+
+        let terms = self
+            .terms
+            .iter()
+            .sorted_by_key(|&(monomial, _)| (std::cmp::Reverse(monomial.len()), monomial));
+        for (idx, (monomial, &coefficient)) in terms.enumerate() {
+            if idx > 0 {
+                write!(f, " {} ", if coefficient < 0 { '-' } else { '+' })?;
+            } else if coefficient < 0 {
+                write!(f, "-")?;
+            }
+
+            let magnitude = coefficient.unsigned_abs();
+            if monomial.is_empty() || magnitude != 1 {
+                write!(f, "{magnitude}")?;
+                if !monomial.is_empty() {
+                    write!(f, "*")?;
+                }
+            }
+            write!(f, "{}", monomial.iter().join("*"))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Known(Polynomial),
+    // Not representable as a polynomial, but depends on at most these symbols
+    Opaque(BTreeSet<Symbol>),
+}
+
+impl Value {
+    pub fn constant(value: i64) -> Self {
+        Self::Known(Polynomial::constant(value))
+    }
+
+    pub fn as_constant(&self) -> Option<i64> {
+        match self {
+            Self::Known(polynomial) => polynomial.as_constant(),
+            Self::Opaque(_) => None,
+        }
+    }
+
+    pub fn symbols(&self) -> BTreeSet<Symbol> {
+        match self {
+            Self::Known(polynomial) => polynomial.symbols(),
+            Self::Opaque(symbols) => symbols.clone(),
+        }
+    }
+
+    fn combine(
+        &self,
+        other: &Self,
+        concrete: impl Fn(i64, i64) -> i64,
+        symbolic: impl Fn(&Polynomial, &Polynomial) -> Option<Polynomial>,
+    ) -> Self {
+        if let (Some(a), Some(b)) = (self.as_constant(), other.as_constant()) {
+            return Self::constant(concrete(a, b));
+        }
+
+        let known = match (self, other) {
+            (Self::Known(a), Self::Known(b)) => symbolic(a, b),
+            _ => None,
+        };
+
+        known.map(Self::Known).unwrap_or_else(|| {
+            Self::Opaque(self.symbols().union(&other.symbols()).copied().collect())
+        })
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        self.combine(other, i64::wrapping_add, Polynomial::checked_add)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        self.combine(other, i64::wrapping_mul, Polynomial::checked_mul)
+    }
+
+    fn less_than(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| (a < b) as i64, |_, _| None)
+    }
+
+    fn equals(&self, other: &Self) -> Self {
+        self.combine(
+            other,
+            |a, b| (a == b) as i64,
+            |a, b| {
+                // Polynomials differing by a non-zero constant can never be equal
+                let difference = a.checked_add(&b.checked_mul(&Polynomial::constant(-1))?)?;
+                difference
+                    .as_constant()
+                    .filter(|&difference| difference != 0)
+                    .map(|_| Polynomial::constant(0))
+            },
+        )
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Known(polynomial) => write!(f, "{polynomial}"),
+            Self::Opaque(symbols) => write!(f, "f({})", symbols.iter().join(", ")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Memory(usize),
+    Output(usize),
+}
+
+#[derive(Debug, Clone)]
+enum Input {
+    Concrete(i64),
+    Symbolic(Symbol),
+}
+
+// Executes a program with some memory cells and inputs left unknown. Whenever control flow,
+// an instruction or a write address depends on unknowns, the path is re-executed from the
+// start once for every combination of values of those unknowns.
+#[derive(Debug, Clone)]
+pub struct Symbolic {
+    program: Box<[i64]>,
+    cells: Vec<(usize, Symbol)>,
+    inputs: Vec<Input>,
+    domains: Vec<RangeInclusive<i64>>,
+    max_paths: usize,
+    max_steps: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Path {
+    pub assignment: Assignment,
+    pub outcome: core::result::Result<State, Error>,
+    pub outputs: Vec<Value>,
+    memory: HashMap<usize, Value>,
+}
+
+impl Path {
+    pub fn value(&self, target: Target) -> Option<Value> {
+        match target {
+            Target::Memory(address) => Some(
+                self.memory
+                    .get(&address)
+                    .cloned()
+                    .unwrap_or(Value::constant(0)),
+            ),
+            Target::Output(idx) => self.outputs.get(idx).cloned(),
+        }
+    }
+}
+
+impl Symbolic {
+    pub fn new(program: impl AsRef<[i64]>) -> Self {
+        Self {
+            program: program.as_ref().into(),
+            cells: Vec::new(),
+            inputs: Vec::new(),
+            domains: Vec::new(),
+            max_paths: 100_000,
+            max_steps: 1_000_000,
+        }
+    }
+
+    pub fn with_limits(mut self, max_paths: usize, max_steps: usize) -> Self {
+        self.max_paths = max_paths;
+        self.max_steps = max_steps;
+        self
+    }
+
+    pub fn cell(&mut self, address: usize, domain: RangeInclusive<i64>) -> Symbol {
+        let symbol = self.symbol(domain);
+        self.cells.push((address, symbol));
+        symbol
+    }
+
+    pub fn input(&mut self, domain: RangeInclusive<i64>) -> Symbol {
+        let symbol = self.symbol(domain);
+        self.inputs.push(Input::Symbolic(symbol));
+        symbol
+    }
+
+    pub fn concrete_input(&mut self, value: i64) {
+        self.inputs.push(Input::Concrete(value));
+    }
+
+    fn symbol(&mut self, domain: RangeInclusive<i64>) -> Symbol {
+        assert!(!domain.is_empty());
+        self.domains.push(domain);
+        Symbol(self.domains.len() - 1)
+    }
+
+    fn all_symbols(&self) -> impl Iterator<Item = Symbol> {
+        (0..self.domains.len()).map(Symbol)
+    }
+
+    fn assignments(&self, symbols: &[Symbol]) -> Box<dyn Iterator<Item = Assignment> + '_> {
+        if symbols.is_empty() {
+            return Box::new(std::iter::once(Assignment::new()));
+        }
+
+        let symbols = symbols.to_vec();
+        let values = symbols
+            .iter()
+            .map(|symbol| self.domains[symbol.0].clone())
+            .multi_cartesian_product();
+        Box::new(values.map(move |values| symbols.iter().copied().zip(values).collect()))
+    }
+
+    pub fn explore(&self) -> Result<Vec<Path>> {
+        let mut queue = VecDeque::from([Assignment::new()]);
+        let mut paths = Vec::new();
+
+        while let Some(assignment) = queue.pop_front() {
+            match self.execute(&assignment) {
+                Ok(path) => paths.push(path),
+                Err(blocking) => {
+                    let free = blocking
+                        .into_iter()
+                        .filter(|symbol| !assignment.contains_key(symbol))
+                        .collect_vec();
+                    assert!(!free.is_empty(), "blocked on assigned symbols");
+
+                    for extension in self.assignments(&free) {
+                        if queue.len() + paths.len() >= self.max_paths {
+                            return Err(Error::PathLimit {
+                                limit: self.max_paths,
+                            });
+                        }
+
+                        let mut assignment = assignment.clone();
+                        assignment.extend(extension);
+                        queue.push_back(assignment);
+                    }
+                }
+            }
+        }
+
+        tracing::debug!(paths = paths.len(), "explored");
+        Ok(paths)
+    }
+
+    pub fn solve(&self, target: Target, expected: i64) -> Result<Option<Assignment>> {
+        for path in self.explore()? {
+            if path.outcome.is_err() {
+                continue;
+            }
+
+            let Some(value) = path.value(target) else {
+                continue;
+            };
+
+            let solution = match value {
+                Value::Known(polynomial) => self.solve_polynomial(&path, &polynomial, expected),
+                Value::Opaque(symbols) => self.search(&path, &symbols, target, expected)?,
+            };
+
+            if let Some(solution) = solution {
+                return Ok(Some(self.complete(solution)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub fn influences(&self, target: Target) -> Result<BTreeSet<Symbol>> {
+        let paths = self.explore()?;
+
+        let mut influences = BTreeSet::new();
+        for path in paths.iter() {
+            if let Some(value) = path.value(target) {
+                influences.extend(value.symbols());
+            }
+
+            // Symbols that decide which path is taken influence everything downstream
+            if paths.len() > 1 {
+                influences.extend(path.assignment.keys());
+            }
+        }
+
+        Ok(influences)
+    }
+
+    fn solve_polynomial(
+        &self,
+        path: &Path,
+        polynomial: &Polynomial,
+        expected: i64,
+    ) -> Option<Assignment> {
+        let free = polynomial
+            .symbols()
+            .into_iter()
+            .filter(|symbol| !path.assignment.contains_key(symbol))
+            .collect_vec();
+
+        let linear = free
+            .iter()
+            .copied()
+            .find_map(|symbol| Some((symbol, polynomial.linear_in(symbol)?)));
+
+        let Some((symbol, (coefficient, rest))) = linear else {
+            return self.assignments(&free).find_map(|extension| {
+                let mut assignment = path.assignment.clone();
+                assignment.extend(extension);
+                (polynomial.evaluate(&assignment)? == expected).then_some(assignment)
+            });
+        };
+
+        let others = free.into_iter().filter(|&s| s != symbol).collect_vec();
+        let domain = self.domains[symbol.0].clone();
+        self.assignments(&others).find_map(|extension| {
+            let mut assignment = path.assignment.clone();
+            assignment.extend(extension);
+
+            let coefficient = coefficient.evaluate(&assignment)?;
+            let remainder = expected.checked_sub(rest.evaluate(&assignment)?)?;
+            let value = if coefficient == 0 {
+                (remainder == 0).then_some(*domain.start())?
+            } else {
+                (remainder % coefficient == 0).then_some(remainder / coefficient)?
+            };
+
+            domain.contains(&value).then(|| {
+                assignment.insert(symbol, value);
+                assignment
+            })
+        })
+    }
+
+    fn search(
+        &self,
+        path: &Path,
+        symbols: &BTreeSet<Symbol>,
+        target: Target,
+        expected: i64,
+    ) -> Result<Option<Assignment>> {
+        let free = symbols
+            .iter()
+            .copied()
+            .filter(|symbol| !path.assignment.contains_key(symbol))
+            .collect_vec();
+
+        for extension in self.assignments(&free) {
+            let mut assignment = path.assignment.clone();
+            assignment.extend(extension);
+            let assignment = self.complete(assignment);
+
+            if self.evaluate(&assignment, target)? == Some(expected) {
+                return Ok(Some(assignment));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn complete(&self, mut assignment: Assignment) -> Assignment {
+        for symbol in self.all_symbols() {
+            assignment
+                .entry(symbol)
+                .or_insert(*self.domains[symbol.0].start());
+        }
+        assignment
+    }
+
+    pub fn evaluate(&self, assignment: &Assignment, target: Target) -> Result<Option<i64>> {
+        let mut program = self.program.to_vec();
+        for &(address, symbol) in self.cells.iter() {
+            if address >= program.len() {
+                program.resize(address + 1, 0);
+            }
+            program[address] = assignment[&symbol];
+        }
+
+        let mut machine = Intcode::new(program);
+        for input in self.inputs.iter() {
+            match input {
+                Input::Concrete(value) => machine.push_input(*value),
+                Input::Symbolic(symbol) => machine.push_input(assignment[symbol]),
+            }
+        }
+        machine.run()?;
+
+        Ok(match target {
+            Target::Memory(address) => Some(machine.get_memory().read(address)),
+            Target::Output(idx) => machine.drain_output().get(idx).copied(),
+        })
+    }
+
+    fn execute(&self, assignment: &Assignment) -> core::result::Result<Path, BTreeSet<Symbol>> {
+        let resolve = |symbol: Symbol| match assignment.get(&symbol) {
+            Some(&value) => Value::constant(value),
+            None => Value::Known(Polynomial::symbol(symbol)),
+        };
+
+        let mut memory: HashMap<usize, Value> = self
+            .program
+            .iter()
+            .enumerate()
+            .map(|(address, &value)| (address, Value::constant(value)))
+            .collect();
+        for &(address, symbol) in self.cells.iter() {
+            memory.insert(address, resolve(symbol));
+        }
+
+        let inputs = self
+            .inputs
+            .iter()
+            .map(|input| match input {
+                Input::Concrete(value) => Value::constant(*value),
+                Input::Symbolic(symbol) => resolve(*symbol),
+            })
+            .collect();
+
+        let mut machine = Machine {
+            memory,
+            instruction_pointer: 0,
+            relative_base: Value::constant(0),
+            inputs,
+            outputs: Vec::new(),
+        };
+
+        let mut outcome = Err(Error::StepLimit {
+            limit: self.max_steps,
+        });
+        for _ in 0..self.max_steps {
+            match machine.step() {
+                Ok(State::Running) => continue,
+                Ok(state) => outcome = Ok(state),
+                Err(Stop::Failed(err)) => outcome = Err(err),
+                Err(Stop::Blocked(symbols)) => return Err(symbols),
+            }
+            break;
+        }
+
+        Ok(Path {
+            assignment: assignment.clone(),
+            outcome,
+            outputs: machine.outputs,
+            memory: machine.memory,
+        })
+    }
+}
+
+enum Stop {
+    Failed(Error),
+    Blocked(BTreeSet<Symbol>),
+}
+
+impl From<Error> for Stop {
+    fn from(value: Error) -> Self {
+        Self::Failed(value)
+    }
+}
+
+struct Machine {
+    memory: HashMap<usize, Value>,
+    instruction_pointer: i64,
+    relative_base: Value,
+    inputs: VecDeque<Value>,
+    outputs: Vec<Value>,
+}
+
+impl Machine {
+    fn step(&mut self) -> core::result::Result<State, Stop> {
+        let ip = self.instruction_pointer;
+        let instruction = self.read(&Value::constant(ip))?;
+        let instruction = concrete(&instruction)?;
+        let instruction = Instruction::decode(ip, instruction)?;
+        let modes = instruction.parameter_modes;
+
+        match instruction.opcode {
+            1 | 2 | 7 | 8 => {
+                let a = self.parameter(modes[0], ip + 1)?;
+                let b = self.parameter(modes[1], ip + 2)?;
+                let value = match instruction.opcode {
+                    1 => a.add(&b),
+                    2 => a.mul(&b),
+                    7 => a.less_than(&b),
+                    _ => a.equals(&b),
+                };
+                self.write(modes[2], ip + 3, value)?;
+                self.instruction_pointer += 4;
+            }
+            3 => {
+                let Some(input) = self.inputs.pop_front() else {
+                    return Ok(State::WaitingForInput);
+                };
+                self.write(modes[0], ip + 1, input)?;
+                self.instruction_pointer += 2;
+            }
+            4 => {
+                let output = self.parameter(modes[0], ip + 1)?;
+                self.outputs.push(output);
+                self.instruction_pointer += 2;
+            }
+            5 | 6 => {
+                let condition = concrete(&self.parameter(modes[0], ip + 1)?)?;
+                if (condition != 0) == (instruction.opcode == 5) {
+                    let target = self.parameter(modes[1], ip + 2)?;
+                    self.instruction_pointer = concrete(&target)?;
+                } else {
+                    self.instruction_pointer += 3;
+                }
+            }
+            9 => {
+                let offset = self.parameter(modes[0], ip + 1)?;
+                self.relative_base = self.relative_base.add(&offset);
+                self.instruction_pointer += 2;
+            }
+            99 => return Ok(State::Terminated),
+            opcode => {
+                return Err(Stop::Failed(Error::UnknownOpcode {
+                    position: ip,
+                    opcode,
+                }))
+            }
+        }
+
+        Ok(State::Running)
+    }
+
+    fn address(&self, mode: AddressingMode, position: i64) -> core::result::Result<Value, Stop> {
+        let parameter = self.read(&Value::constant(position))?;
+        Ok(match mode {
+            AddressingMode::Position | AddressingMode::Immediate => parameter,
+            AddressingMode::Relative => parameter.add(&self.relative_base),
+        })
+    }
+
+    fn parameter(&self, mode: AddressingMode, position: i64) -> core::result::Result<Value, Stop> {
+        let address = self.address(mode, position)?;
+        match mode {
+            AddressingMode::Immediate => Ok(address),
+            _ => self.read(&address),
+        }
+    }
+
+    fn read(&self, address: &Value) -> core::result::Result<Value, Stop> {
+        let Some(address) = address.as_constant() else {
+            // Unknown address, so the result may depend on anything in memory
+            let mut symbols = address.symbols();
+            for value in self.memory.values() {
+                symbols.extend(value.symbols());
+            }
+            return Ok(Value::Opaque(symbols));
+        };
+
+        if address < 0 {
+            return Err(self.illegal_access(address));
+        }
+
+        Ok(self
+            .memory
+            .get(&(address as usize))
+            .cloned()
+            .unwrap_or(Value::constant(0)))
+    }
+
+    fn write(
+        &mut self,
+        mode: AddressingMode,
+        position: i64,
+        value: Value,
+    ) -> core::result::Result<(), Stop> {
+        let address = self.address(mode, position)?;
+        let address = match mode {
+            AddressingMode::Immediate => position,
+            _ => concrete(&address)?,
+        };
+
+        if address < 0 {
+            return Err(self.illegal_access(address));
+        }
+
+        self.memory.insert(address as usize, value);
+        Ok(())
+    }
+
+    fn illegal_access(&self, address: i64) -> Stop {
+        Stop::Failed(Error::IllegalMemoryAccess {
+            position: self.instruction_pointer,
+            address,
+        })
+    }
+}
+
+fn concrete(value: &Value) -> core::result::Result<i64, Stop> {
+    value
+        .as_constant()
+        .ok_or_else(|| Stop::Blocked(value.symbols()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::intcode::fuzz::Rng;
+    use rstest::*;
+
+    // mem[0] = mem[1] * 3 + mem[2] + 7, after first adding mem[mem[1]] and mem[mem[2]] into a
+    // cell that is never used again
+    const LINEAR: &[i64] = &[
+        1, 0, 0, 3, 1002, 1, 3, 19, 1, 19, 2, 19, 1001, 19, 7, 0, 99, 0, 0, 0,
+    ];
+
+    #[rstest]
+    fn test_polynomial_output() -> Result<()> {
+        crate::util::test::setup_tracing();
+
+        let mut symbolic = Symbolic::new(LINEAR);
+        let noun = symbolic.cell(1, 0..=99);
+        let verb = symbolic.cell(2, 0..=99);
+
+        let paths = symbolic.explore()?;
+        assert_eq!(paths.len(), 1);
+
+        let value = paths[0].value(Target::Memory(0)).unwrap();
+        assert_eq!(value.to_string(), "3*x0 + x1 + 7");
+        assert_eq!(
+            symbolic.influences(Target::Memory(0))?,
+            BTreeSet::from([noun, verb])
+        );
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(7, Some((0, 0)))]
+    #[case(200, Some((64, 1)))]
+    #[case(400, Some((99, 96)))]
+    #[case(500, Some((99, 196)))]
+    #[case(600, None)]
+    fn test_solve(#[case] expected: i64, #[case] solution: Option<(i64, i64)>) -> Result<()> {
+        crate::util::test::setup_tracing();
+
+        let mut symbolic = Symbolic::new(LINEAR);
+        let noun = symbolic.cell(1, 0..=99);
+        let verb = symbolic.cell(2, 0..=199);
+
+        let result = symbolic.solve(Target::Memory(0), expected)?;
+        let result = result.map(|assignment| (assignment[&noun], assignment[&verb]));
+        assert_eq!(result, solution);
+
+        if let Some((noun, verb)) = result {
+            assert_eq!(3 * noun + verb + 7, expected);
+        }
+        Ok(())
+    }
+
+    #[rstest]
+    fn test_branch_falls_back_to_concrete_search() -> Result<()> {
+        crate::util::test::setup_tracing();
+
+        // Day 05 example: outputs 999 below 8, 1000 at 8 and 1001 above 8
+        let program = [
+            3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0,
+            0, 1002, 21, 125, 20, 4, 20, 1105, 1, 46, 104, 999, 1105, 1, 46, 1101, 1000, 1, 20, 4,
+            20, 1105, 1, 46, 98, 99,
+        ];
+
+        let mut symbolic = Symbolic::new(program);
+        let input = symbolic.input(0..=20);
+
+        assert_eq!(symbolic.explore()?.len(), 21);
+        assert_eq!(
+            symbolic.influences(Target::Output(0))?,
+            BTreeSet::from([input])
+        );
+
+        let result = symbolic.solve(Target::Output(0), 1000)?;
+        assert_eq!(result.map(|assignment| assignment[&input]), Some(8));
+        let result = symbolic.solve(Target::Output(0), 1001)?;
+        assert_eq!(result.map(|assignment| assignment[&input]), Some(9));
+        Ok(())
+    }
+
+    #[rstest]
+    fn test_matches_concrete_execution() -> Result<()> {
+        crate::util::test::setup_tracing();
+
+        let mut rng = Rng::new(0);
+        for seed in 0..40 {
+            let case = crate::puzzle::intcode::fuzz::generate(seed, 48);
+
+            let mut symbolic = Symbolic::new(&case.program).with_limits(50_000, 100_000);
+            for (idx, &input) in case.inputs.iter().enumerate() {
+                if idx < 2 {
+                    symbolic.input(-2..=2);
+                } else {
+                    symbolic.concrete_input(input);
+                }
+            }
+
+            let paths = symbolic.explore()?;
+            for _ in 0..10 {
+                let assignment = symbolic.complete(
+                    symbolic
+                        .all_symbols()
+                        .map(|symbol| (symbol, rng.between(-2, 2)))
+                        .collect(),
+                );
+
+                let path = paths
+                    .iter()
+                    .find(|path| {
+                        path.assignment
+                            .iter()
+                            .all(|(symbol, value)| assignment[symbol] == *value)
+                    })
+                    .expect("every assignment is covered by a path");
+
+                for (idx, output) in path.outputs.iter().enumerate() {
+                    let expected = symbolic.evaluate(&assignment, Target::Output(idx))?;
+                    if let Value::Known(polynomial) = output {
+                        assert_eq!(polynomial.evaluate(&assignment), expected, "seed {seed}");
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
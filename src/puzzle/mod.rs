@@ -1,71 +1,331 @@
-use std::path::{Path, PathBuf};
-
-use crate::benchmark::RuntimeStats;
-
-mod intcode;
-pub mod template;
-
-pub mod day01;
-pub mod day02;
-pub mod day03;
-pub mod day04;
-pub mod day05;
-pub mod day06;
-pub mod day07;
-pub mod day08;
-pub mod day09;
-pub mod day10;
-pub mod day11;
-pub mod day12;
-pub mod day13;
-pub mod day14;
-pub mod day15;
-pub mod day16;
-pub mod day17;
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crate::benchmark::{sample, DurationFormatter, Sampling, Timings};
+
+pub mod intcode;
+
+// Declares every day module and registers its solution with the runner
+macro_rules! puzzles {
+    ($($day:ident),* $(,)?) => {
+        $(pub mod $day;)*
+
+        pub fn registry(sampling: Sampling) -> Registry {
+            Registry::new([$(Puzzle::of::<$day::Day>(sampling)),*])
+        }
+    };
+}
+
+puzzles! {
+    template, day01, day02, day03, day04, day05, day06, day07, day08, day09,
+    day10, day11, day12, day13, day14, day15, day16, day17,
+}
+
+// Directory holding every puzzle's `dayNN` input directory, unless moved with
+// `Registry::with_input_dir`
+pub const INPUT_DIR: &str = "inputs";
+
+// The template is registered as day 0 so it can be run explicitly, but is skipped when running
+// every puzzle
+pub const TEMPLATE: u32 = 0;
+
+pub trait Solution {
+    const DAY: u32;
+    const INPUT_FILE: &'static str;
+    const EXAMPLES: &'static [Example] = &[];
+
+    // Parsed input shared between both parts, which may borrow from the raw input
+    type Input<'a>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>>;
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display>;
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display>;
+
+    // Shows the solution at work as a sequence of frames, for the puzzles that have something
+    // to show
+    fn visualize(_input: &Self::Input<'_>, _frames: &mut dyn Frames) -> Result<()> {
+        Err(Error::NoVisualization)
+    }
+}
+
+// Receives the frames of a visualisation one by one
+pub trait Frames {
+    fn frame(&mut self, frame: &dyn std::fmt::Display) -> Result<()>;
+}
+
+#[cfg(test)]
+impl Frames for Vec<String> {
+    fn frame(&mut self, frame: &dyn std::fmt::Display) -> Result<()> {
+        self.push(frame.to_string());
+        Ok(())
+    }
+}
+
+// An example input `inputs/dayNN/test.N.txt` together with the expected answer for one part
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Example {
+    pub part: u32,
+    pub which: usize,
+    pub expected: &'static str,
+}
+
+impl Example {
+    pub const fn new(part: u32, which: usize, expected: &'static str) -> Self {
+        Self {
+            part,
+            which,
+            expected,
+        }
+    }
+}
+
+pub struct Registry {
+    puzzles: Vec<Puzzle>,
+}
+
+impl Registry {
+    fn new(puzzles: impl IntoIterator<Item = Puzzle>) -> Self {
+        let mut puzzles = Vec::from_iter(puzzles);
+        puzzles.sort_by_key(|puzzle| puzzle.puzzle);
+
+        for (previous, next) in puzzles.iter().zip(puzzles.iter().skip(1)) {
+            assert_ne!(previous.puzzle, next.puzzle, "puzzle registered twice");
+        }
+
+        Self { puzzles }
+    }
+
+    // Reads the inputs and examples from `directory` instead of `inputs`
+    pub fn with_input_dir(mut self, directory: &Path) -> Self {
+        for puzzle in self.puzzles.iter_mut() {
+            if let Ok(file) = puzzle.input_file.strip_prefix(INPUT_DIR) {
+                puzzle.input_file = directory.join(file);
+            }
+        }
+        self
+    }
+
+    pub fn get(&self, puzzle: u32) -> Option<&Puzzle> {
+        self.puzzles
+            .binary_search_by_key(&puzzle, |entry| entry.puzzle)
+            .ok()
+            .map(|idx| &self.puzzles[idx])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Puzzle> {
+        self.puzzles.iter()
+    }
+}
+
+// Where a puzzle reads its input from, `Default` being the puzzle's own input file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Input {
+    #[default]
+    Default,
+    File(PathBuf),
+    Stdin,
+    Example(usize),
+}
+
+impl Input {
+    pub fn new(path: Option<PathBuf>, example: Option<usize>) -> Self {
+        match (path, example) {
+            (Some(path), _) if path.as_os_str() == "-" => Self::Stdin,
+            (Some(path), _) => Self::File(path),
+            (None, Some(which)) => Self::Example(which),
+            (None, None) => Self::Default,
+        }
+    }
+}
+
+type Visitor<'a> = dyn FnMut(u32, crate::benchmark::Result) -> crate::Result<()> + 'a;
 
 #[allow(clippy::type_complexity)]
 pub struct Puzzle {
     puzzle: u32,
     input_file: PathBuf,
-    p1: Box<dyn Fn(&str) -> Result<(RuntimeStats, String)>>,
-    p2: Box<dyn Fn(&str) -> Result<(RuntimeStats, String)>>,
+    examples: &'static [Example],
+    solve: Arc<dyn Fn(&str, [bool; 2], &mut Visitor) -> crate::Result<()> + Send + Sync>,
+    visualize: Box<dyn Fn(&str, &mut dyn Frames) -> Result<()> + Send + Sync>,
 }
 
 impl Puzzle {
-    #[allow(clippy::type_complexity)]
-    pub fn new(
-        id: u32,
-        input_file: impl AsRef<Path>,
-        p1: Box<dyn Fn(&str) -> Result<(RuntimeStats, String)>>,
-        p2: Box<dyn Fn(&str) -> Result<(RuntimeStats, String)>>,
-    ) -> Self {
+    pub fn of<S: Solution + 'static>(sampling: Sampling) -> Self {
         Self {
-            puzzle: id,
-            input_file: input_file.as_ref().to_owned(),
-            p1,
-            p2,
+            puzzle: S::DAY,
+            input_file: PathBuf::from(S::INPUT_FILE),
+            examples: S::EXAMPLES,
+            solve: Arc::new(move |input, parts, visitor| {
+                // The input is parsed once for both parts, so a parse error is only reported
+                // for the first selected part
+                let (parse, parse_allocations, parsed) = match sample(sampling, || S::parse(input))
+                {
+                    Ok(parsed) => parsed,
+                    Err(err) => {
+                        let part = if parts[0] { 1 } else { 2 };
+                        return visitor(part, Err(err));
+                    }
+                };
+
+                if parts[0] {
+                    let result = sample(sampling, || S::part1(&parsed));
+                    let result = result.map(|(solve, solve_allocations, result)| {
+                        let timings = Timings {
+                            parse: parse.clone(),
+                            solve,
+                            parse_allocations,
+                            solve_allocations,
+                        };
+                        (timings, result.to_string())
+                    });
+                    visitor(1, result)?;
+                }
+
+                if parts[1] {
+                    let result = sample(sampling, || S::part2(&parsed));
+                    let result = result.map(|(solve, solve_allocations, result)| {
+                        let timings = Timings {
+                            parse: parse.clone(),
+                            solve,
+                            parse_allocations,
+                            solve_allocations,
+                        };
+                        (timings, result.to_string())
+                    });
+                    visitor(2, result)?;
+                }
+
+                Ok(())
+            }),
+            visualize: Box::new(|input, frames| S::visualize(&S::parse(input)?, frames)),
         }
     }
 
+    pub fn id(&self) -> u32 {
+        self.puzzle
+    }
+
+    pub fn examples(&self) -> &'static [Example] {
+        self.examples
+    }
+
+    pub fn example_file(&self, which: usize) -> PathBuf {
+        let directory = self.input_file.parent().unwrap_or(Path::new(""));
+        directory.join(format!("test.{which}.txt"))
+    }
+
+    pub fn source(&self, input: &Input) -> String {
+        match input {
+            Input::Default => self.input_file.display().to_string(),
+            Input::File(path) => path.display().to_string(),
+            Input::Stdin => "<stdin>".to_string(),
+            Input::Example(which) => self.example_file(*which).display().to_string(),
+        }
+    }
+
+    pub fn read_input(&self, input: &Input) -> std::io::Result<String> {
+        match input {
+            Input::Default => std::fs::read_to_string(&self.input_file),
+            Input::File(path) => std::fs::read_to_string(path),
+            Input::Stdin => std::io::read_to_string(std::io::stdin()),
+            Input::Example(which) => std::fs::read_to_string(self.example_file(*which)),
+        }
+    }
+
+    // Runs the selected parts, on a worker thread when there is a `timeout` for each part. When
+    // a part runs out of time, it and the parts queued behind it are reported as timed out,
+    // leaving the worker to finish in the background. Threads can't be stopped, so the worker
+    // keeps using a core and allocating, which skews the timings and allocation counts of
+    // whatever is measured until it finishes.
     pub fn run(
         &self,
+        input: &Input,
         parts: [bool; 2],
-        mut visitor: impl FnMut(u32, u32, crate::benchmark::Result) -> crate::Result<()>,
+        timeout: Option<Duration>,
+        mut visitor: impl FnMut(u32, u32, &str, crate::benchmark::Result) -> crate::Result<()>,
     ) -> crate::Result<()> {
-        let input = std::fs::read_to_string(&self.input_file)?;
+        let source = self.source(input);
+        let input = self.read_input(input)?;
 
-        if parts[0] {
-            let result = (*self.p1)(input.as_str());
-            visitor(self.puzzle, 1, result)?;
+        if !parts.iter().any(|&part| part) {
+            return Ok(());
         }
 
-        if parts[1] {
-            let result = (*self.p2)(input.as_str());
-            visitor(self.puzzle, 2, result)?;
+        let Some(timeout) = timeout else {
+            return (*self.solve)(&input, parts, &mut |part, result| {
+                visitor(self.puzzle, part, &source, result)
+            });
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        let solve = Arc::clone(&self.solve);
+        let worker = std::thread::Builder::new()
+            .name(format!("day{:02}", self.puzzle))
+            .spawn(move || {
+                (*solve)(&input, parts, &mut |part, result| {
+                    // Nobody listens anymore once a part timed out
+                    let _ = sender.send((part, result));
+                    Ok(())
+                })
+            })?;
+
+        let mut pending = [1, 2]
+            .into_iter()
+            .filter(|&part| parts[part as usize - 1])
+            .collect::<Vec<_>>();
+
+        while !pending.is_empty() {
+            match receiver.recv_timeout(timeout) {
+                Ok((part, result)) => {
+                    pending.retain(|&pending| pending != part);
+                    visitor(self.puzzle, part, &source, result)?;
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    for part in pending {
+                        visitor(self.puzzle, part, &source, Err(Error::Timeout(timeout)))?;
+                    }
+                    break;
+                }
+                // The worker is done, either after a parse error or because it panicked
+                Err(RecvTimeoutError::Disconnected) => {
+                    if worker.join().is_err() {
+                        for part in pending {
+                            visitor(self.puzzle, part, &source, Err(Error::Panicked))?;
+                        }
+                    }
+                    break;
+                }
+            }
         }
 
         Ok(())
     }
+
+    pub fn visualize(&self, input: &Input, frames: &mut dyn Frames) -> Result<()> {
+        let input = self.read_input(input)?;
+        (*self.visualize)(&input, frames)
+    }
+
+    pub fn run_example(&self, example: &Example) -> crate::benchmark::Result {
+        let input =
+            std::fs::read_to_string(self.example_file(example.which)).map_err(Error::from)?;
+
+        let mut outcome = None;
+        let parts = [example.part == 1, example.part == 2];
+        (*self.solve)(&input, parts, &mut |_, result| {
+            outcome = Some(result);
+            Ok(())
+        })
+        .expect("collecting the outcome does not fail");
+
+        outcome.expect("examples are for part 1 or 2")
+    }
 }
 
 pub type Result<T> = core::result::Result<T, Error>;
@@ -74,6 +334,12 @@ pub type Result<T> = core::result::Result<T, Error>;
 pub enum Error {
     #[error("Not implemented")]
     NotImplemented,
+    #[error("timed out after {}", DurationFormatter(*.0))]
+    Timeout(Duration),
+    #[error("Panicked")]
+    Panicked,
+    #[error("Visualisation is not supported")]
+    NoVisualization,
     #[error("{0} error: {1}")]
     String(String, String),
     #[error(transparent)]
@@ -85,8 +351,18 @@ pub enum Error {
 }
 
 impl Error {
-    fn execution(message: &str) -> Self {
-        Self::String("Execution".to_string(), message.to_string())
+    // Short machine-readable name of the variant, such as `timeout` or `parse`
+    pub fn kind(&self) -> String {
+        match self {
+            Self::NotImplemented => "not_implemented".to_string(),
+            Self::Timeout(_) => "timeout".to_string(),
+            Self::Panicked => "panicked".to_string(),
+            Self::NoVisualization => "no_visualization".to_string(),
+            Self::String(kind, _) => kind.to_lowercase(),
+            Self::Intcode(_) => "intcode".to_string(),
+            Self::Parse(_) => "parse".to_string(),
+            Self::Io(_) => "io".to_string(),
+        }
     }
 
     fn input(message: &str) -> Self {
@@ -111,6 +387,12 @@ where
     }
 }
 
+impl From<intcode::FormatError> for Error {
+    fn from(value: intcode::FormatError) -> Self {
+        ParseError::from(value).into()
+    }
+}
+
 impl From<std::num::ParseIntError> for Error {
     fn from(value: std::num::ParseIntError) -> Self {
         ParseError::from(value).into()
@@ -125,6 +407,8 @@ pub enum ParseError {
     Nom(String),
     #[error(transparent)]
     Integer(#[from] std::num::ParseIntError),
+    #[error(transparent)]
+    Program(#[from] intcode::FormatError),
 }
 
 impl<T> From<nom::error::Error<T>> for ParseError
@@ -135,3 +419,216 @@ where
         Self::Nom(format!("{}", value))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn test_registry() {
+        crate::util::test::setup_tracing();
+
+        let registry = registry(Sampling::default());
+        let days = registry.iter().map(Puzzle::id).collect::<Vec<_>>();
+        assert_eq!(days, (0..=17).collect::<Vec<_>>());
+
+        for puzzle in registry.iter().skip(1) {
+            let directory = format!("inputs/day{:02}", puzzle.id());
+            assert!(puzzle.input_file.starts_with(&directory), "{directory}");
+            assert_eq!(
+                puzzle.example_file(3),
+                Path::new(&directory).join("test.3.txt")
+            );
+        }
+
+        assert!(registry.get(0).is_some());
+
+        let puzzle = registry.get(5).expect("day 5 is registered");
+        assert_eq!(puzzle.source(&Input::Default), "inputs/day05/input.txt");
+        assert_eq!(
+            puzzle.source(&Input::new(None, Some(2))),
+            "inputs/day05/test.2.txt"
+        );
+        assert_eq!(
+            puzzle.source(&Input::new(Some("-".into()), None)),
+            "<stdin>"
+        );
+        assert!(registry.get(18).is_none());
+
+        let registry = registry.with_input_dir(Path::new("/data/aoc"));
+        let puzzle = registry.get(5).expect("day 5 is registered");
+        assert_eq!(puzzle.source(&Input::Default), "/data/aoc/day05/input.txt");
+        assert_eq!(
+            puzzle.example_file(1),
+            Path::new("/data/aoc/day05/test.1.txt")
+        );
+    }
+
+    struct Slow;
+
+    impl Solution for Slow {
+        const DAY: u32 = 99;
+        const INPUT_FILE: &'static str = "Cargo.toml";
+
+        type Input<'a> = ();
+
+        fn parse(_: &str) -> Result<Self::Input<'_>> {
+            Ok(())
+        }
+
+        fn part1(_: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+            Ok(1)
+        }
+
+        fn part2(_: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+            std::thread::sleep(Duration::from_millis(500));
+            Ok(2)
+        }
+    }
+
+    #[rstest]
+    fn test_timeout() -> crate::Result<()> {
+        crate::util::test::setup_tracing();
+
+        let puzzle = Puzzle::of::<Slow>(Sampling::default());
+        let mut outcomes = Vec::new();
+        let timeout = Some(Duration::from_millis(100));
+        puzzle.run(
+            &Input::Default,
+            [true, true],
+            timeout,
+            |_, part, _, result| {
+                outcomes.push((part, result.map(|(_, result)| result)));
+                Ok(())
+            },
+        )?;
+
+        assert_eq!(outcomes.len(), 2);
+        assert!(matches!(&outcomes[0], (1, Ok(result)) if result == "1"));
+        assert!(matches!(outcomes[1], (2, Err(Error::Timeout(_)))));
+        Ok(())
+    }
+
+    struct Stuck;
+
+    impl Solution for Stuck {
+        const DAY: u32 = 98;
+        const INPUT_FILE: &'static str = "Cargo.toml";
+
+        type Input<'a> = ();
+
+        fn parse(_: &str) -> Result<Self::Input<'_>> {
+            Ok(())
+        }
+
+        fn part1(_: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+            std::thread::sleep(Duration::from_millis(500));
+            Ok(1)
+        }
+
+        fn part2(_: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+            Ok(2)
+        }
+    }
+
+    #[rstest]
+    fn test_timeout_first_part() -> crate::Result<()> {
+        crate::util::test::setup_tracing();
+
+        let puzzle = Puzzle::of::<Stuck>(Sampling::default());
+        let mut outcomes = Vec::new();
+        let timeout = Some(Duration::from_millis(100));
+        puzzle.run(
+            &Input::Default,
+            [true, true],
+            timeout,
+            |_, part, _, result| {
+                outcomes.push((part, result.map(|(_, result)| result)));
+                Ok(())
+            },
+        )?;
+
+        assert_eq!(outcomes.len(), 2);
+        assert!(matches!(outcomes[0], (1, Err(Error::Timeout(_)))));
+        assert!(matches!(outcomes[1], (2, Err(Error::Timeout(_)))));
+        Ok(())
+    }
+
+    struct Panicky;
+
+    impl Solution for Panicky {
+        const DAY: u32 = 97;
+        const INPUT_FILE: &'static str = "Cargo.toml";
+
+        type Input<'a> = ();
+
+        fn parse(_: &str) -> Result<Self::Input<'_>> {
+            Ok(())
+        }
+
+        fn part1(_: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+            panic!("part 1 panics");
+            #[allow(unreachable_code)]
+            Ok(1)
+        }
+
+        fn part2(_: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+            Ok(2)
+        }
+    }
+
+    #[rstest]
+    fn test_panic_first_part() -> crate::Result<()> {
+        crate::util::test::setup_tracing();
+
+        let puzzle = Puzzle::of::<Panicky>(Sampling::default());
+        let mut outcomes = Vec::new();
+        let timeout = Some(Duration::from_secs(10));
+        puzzle.run(
+            &Input::Default,
+            [true, true],
+            timeout,
+            |_, part, _, result| {
+                outcomes.push((part, result.map(|(_, result)| result)));
+                Ok(())
+            },
+        )?;
+
+        assert_eq!(outcomes.len(), 2);
+        assert!(matches!(outcomes[0], (1, Err(Error::Panicked))));
+        assert!(matches!(outcomes[1], (2, Err(Error::Panicked))));
+        Ok(())
+    }
+
+    #[rstest]
+    fn test_no_visualization() {
+        crate::util::test::setup_tracing();
+
+        let puzzle = Puzzle::of::<Slow>(Sampling::default());
+        let mut frames = Vec::<String>::new();
+        let result = puzzle.visualize(&Input::Default, &mut frames);
+
+        assert!(matches!(result, Err(Error::NoVisualization)));
+        assert!(frames.is_empty());
+    }
+
+    #[rstest]
+    fn test_examples() -> Result<()> {
+        crate::util::test::setup_tracing();
+
+        for puzzle in registry(Sampling::default()).iter() {
+            for example in puzzle.examples() {
+                if !puzzle.example_file(example.which).exists() {
+                    tracing::warn!(puzzle = puzzle.id(), ?example, "example input missing");
+                    continue;
+                }
+
+                let (_, result) = puzzle.run_example(example)?;
+                assert_eq!(result, example.expected, "{} {example:?}", puzzle.id());
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,19 +1,30 @@
-use super::{Error, Result};
+use super::{Error, Result, Solution};
 
-pub const INPUT_FILE: &str = "inputs/template/input.txt";
+pub struct Day;
 
-pub fn part1(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part1(input)
+impl Solution for Day {
+    const DAY: u32 = 0;
+    const INPUT_FILE: &'static str = "inputs/template/input.txt";
+
+    type Input<'a> = &'a str;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>> {
+        Ok(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> Result<impl std::fmt::Display> {
+        solve_part2(input)
+    }
 }
 
 fn solve_part1(_input: &str) -> Result<&'static str> {
     Err(Error::NotImplemented)
 }
 
-pub fn part2(input: &str) -> Result<impl std::fmt::Display> {
-    solve_part2(input)
-}
-
 fn solve_part2(_input: &str) -> Result<&'static str> {
     Err(Error::NotImplemented)
 }
@@ -1,42 +1,576 @@
-use std::path::Path;
+use std::{
+    fmt::Write,
+    path::Path,
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 
-use crate::{benchmark::RuntimeStats, Error};
+use crate::{
+    benchmark::{DurationFormatter, Rounds, RuntimeStats, Sampling, Timings},
+    memory::{Allocations, ByteFormatter},
+    Error,
+};
 
-#[derive(Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    // A `aoc2019 report,<version>` header, then rows of `Day N,Part M,<source>,<phase>,<samples>...`,
+    // which can be loaded back.
+    // Counted allocations follow as `Day N,Part M,<source>,<phase> allocations,<count>,<bytes>,<peak>`
+    #[default]
+    Csv,
+    Json,
+    Markdown,
+}
+
+// Phase of the entry recorded for a part that timed out
+pub const TIMEOUT: &str = "Timeout";
+
+// First record of a CSV report, bumped whenever the row layout changes
+const CSV_MAGIC: &str = "aoc2019 report";
+const CSV_VERSION: u32 = 2;
+
+// How and when a report was recorded, which a CSV report doesn't keep
+#[derive(Debug, Clone)]
+pub struct Metadata {
+    pub sampling: Sampling,
+    pub timestamp: SystemTime,
+    pub revision: Option<String>,
+}
+
+impl Metadata {
+    pub fn new(sampling: Sampling) -> Self {
+        Self {
+            sampling,
+            timestamp: SystemTime::now(),
+            revision: git_revision(),
+        }
+    }
+}
+
+fn git_revision() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub puzzle: u32,
+    pub part: u32,
+    pub source: String,
+    pub phase: String,
+    // Run times in milliseconds
+    pub samples: Vec<f64>,
+    pub allocations: Option<Allocations>,
+}
+
+impl Entry {
+    pub fn key(&self) -> (u32, u32, &str, &str) {
+        (self.puzzle, self.part, &self.source, &self.phase)
+    }
+
+    pub fn is_timeout(&self) -> bool {
+        self.phase == TIMEOUT
+    }
+
+    pub fn median(&self) -> f64 {
+        let mut sorted = self.samples.clone();
+        sorted.sort_by(f64::total_cmp);
+
+        let mid = sorted.len() / 2;
+        if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) / 2.0
+        } else {
+            sorted[mid]
+        }
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.samples.iter().sum::<f64>() / self.samples.len() as f64
+    }
+
+    pub fn min(&self) -> f64 {
+        self.samples.iter().copied().fold(f64::INFINITY, f64::min)
+    }
+
+    pub fn max(&self) -> f64 {
+        self.samples
+            .iter()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+}
+
+#[derive(Debug, Default)]
 pub struct Report {
-    entries: Vec<(u32, u32, Vec<f64>)>,
+    metadata: Option<Metadata>,
+    entries: Vec<Entry>,
 }
 
 impl Report {
-    pub fn push_entry(&mut self, puzzle: u32, part: u32, stats: &RuntimeStats) {
-        self.entries.push((
+    pub fn new(metadata: Metadata) -> Self {
+        Self {
+            metadata: Some(metadata),
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn push_entry(&mut self, puzzle: u32, part: u32, source: &str, timings: &Timings) {
+        let phases = [
+            ("Parse", &timings.parse, timings.parse_allocations),
+            ("Solve", &timings.solve, timings.solve_allocations),
+        ];
+
+        for (phase, stats, allocations) in phases {
+            self.push(Entry {
+                puzzle,
+                part,
+                source: source.to_string(),
+                phase: phase.to_string(),
+                samples: Self::samples(stats),
+                allocations,
+            });
+        }
+    }
+
+    // A part that timed out is recorded with the timeout as its only sample
+    pub fn push_timeout(&mut self, puzzle: u32, part: u32, source: &str, timeout: Duration) {
+        self.push(Entry {
             puzzle,
             part,
-            stats
-                .runs()
-                .iter()
-                .map(|run| run.as_nanos() as f64 / 1_000_000.0)
-                .collect_vec(),
-        ));
+            source: source.to_string(),
+            phase: TIMEOUT.to_string(),
+            samples: vec![timeout.as_secs_f64() * 1000.0],
+            allocations: None,
+        });
+    }
+
+    pub fn push(&mut self, entry: Entry) {
+        self.entries.push(entry);
+    }
+
+    fn samples(stats: &RuntimeStats) -> Vec<f64> {
+        stats
+            .runs()
+            .iter()
+            .map(|run| run.as_nanos() as f64 / 1_000_000.0)
+            .collect_vec()
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
     }
 
-    pub fn save_to(&self, path: impl AsRef<Path>) -> Result<(), Error> {
-        let mut writer = csv::Writer::from_path(path)?;
+    pub fn save_to(&self, path: impl AsRef<Path>, format: Format) -> Result<(), Error> {
+        match format {
+            Format::Csv => self.write_csv(path),
+            Format::Json => {
+                let file = std::fs::File::create(path)?;
+                serde_json::to_writer_pretty(std::io::BufWriter::new(file), &self.json())?;
+                Ok(())
+            }
+            Format::Markdown => Ok(std::fs::write(path, self.markdown())?),
+        }
+    }
 
-        for (puzzle, part, values) in self.entries.iter() {
-            let mut record = csv::StringRecord::with_capacity(1024, values.len() + 2);
-            record.push_field(format!("Day {puzzle}").as_str());
-            record.push_field(format!("Part {part}").as_str());
+    fn write_csv(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let mut writer = csv::WriterBuilder::new().flexible(true).from_path(path)?;
+        writer.write_record([CSV_MAGIC, CSV_VERSION.to_string().as_str()])?;
 
-            for value in values.iter() {
+        for entry in self.entries.iter() {
+            let mut record = csv::StringRecord::with_capacity(1024, entry.samples.len() + 4);
+            record.push_field(format!("Day {}", entry.puzzle).as_str());
+            record.push_field(format!("Part {}", entry.part).as_str());
+            record.push_field(&entry.source);
+            record.push_field(&entry.phase);
+
+            for value in entry.samples.iter() {
                 record.push_field(format!("{value}").as_str());
             }
 
             writer.write_record(&record)?;
+
+            if let Some(allocations) = entry.allocations {
+                writer.write_record([
+                    format!("Day {}", entry.puzzle),
+                    format!("Part {}", entry.part),
+                    entry.source.clone(),
+                    format!("{} allocations", entry.phase),
+                    allocations.count.to_string(),
+                    allocations.bytes.to_string(),
+                    allocations.peak.to_string(),
+                ])?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn load_from(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_path(path)?;
+        Self::read(reader)
+    }
+
+    fn read<R: std::io::Read>(mut reader: csv::Reader<R>) -> Result<Self, Error> {
+        let mut records = reader.records();
+        let header = records.next().transpose()?;
+        match header.as_ref().map(|header| (header.get(0), header.get(1))) {
+            Some((Some(CSV_MAGIC), Some(version))) if version == CSV_VERSION.to_string() => (),
+            Some((Some(CSV_MAGIC), version)) => {
+                return Err(Error::report(
+                    1,
+                    format!(
+                        "unsupported report version {}, expected {CSV_VERSION}",
+                        version.unwrap_or("(none)")
+                    ),
+                ))
+            }
+            Some(_) => {
+                return Err(Error::report(
+                    1,
+                    format!(
+                        "missing \"{CSV_MAGIC},{CSV_VERSION}\" header; reports recorded before it was added have to be recorded again"
+                    ),
+                ))
+            }
+            None => return Err(Error::report(1, "empty report")),
+        }
+
+        let mut entries = Vec::new();
+
+        for (idx, record) in records.enumerate() {
+            let record = record?;
+            let line = idx + 2;
+
+            let field = |idx: usize, prefix: &str| {
+                record
+                    .get(idx)
+                    .and_then(|field| field.strip_prefix(prefix))
+                    .and_then(|field| field.parse::<u32>().ok())
+                    .ok_or_else(|| Error::report(line, format!("expected \"{prefix}N\"")))
+            };
+
+            let puzzle = field(0, "Day ")?;
+            let part = field(1, "Part ")?;
+            let (Some(source), Some(phase)) = (record.get(2), record.get(3)) else {
+                return Err(Error::report(line, "missing source or phase"));
+            };
+
+            if let Some(phase) = phase.strip_suffix(" allocations") {
+                let values = record
+                    .iter()
+                    .skip(4)
+                    .map(|value| value.parse::<u64>().ok())
+                    .collect::<Option<Vec<_>>>();
+                let Some(&[count, bytes, peak]) = values.as_deref() else {
+                    return Err(Error::report(line, "expected count, bytes and peak"));
+                };
+
+                let entry = entries
+                    .last_mut()
+                    .filter(|entry: &&mut Entry| entry.key() == (puzzle, part, source, phase))
+                    .ok_or_else(|| Error::report(line, "allocations without timings"))?;
+                entry.allocations = Some(Allocations { count, bytes, peak });
+                continue;
+            }
+
+            let samples = record
+                .iter()
+                .skip(4)
+                .map(|value| {
+                    value
+                        .parse::<f64>()
+                        .map_err(|_| Error::report(line, format!("invalid sample {value:?}")))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if samples.is_empty() {
+                return Err(Error::report(line, "no samples"));
+            }
+
+            entries.push(Entry {
+                puzzle,
+                part,
+                source: source.to_string(),
+                phase: phase.to_string(),
+                samples,
+                allocations: None,
+            });
         }
 
+        Ok(Self {
+            metadata: None,
+            entries,
+        })
+    }
+
+    fn json(&self) -> JsonReport<'_> {
+        let metadata = self.metadata.as_ref().map(|metadata| JsonMetadata {
+            timestamp: metadata
+                .timestamp
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            revision: metadata.revision.as_deref(),
+            warmup: metadata.sampling.warmup,
+            rounds: match metadata.sampling.rounds {
+                Rounds::Fixed(rounds) => JsonRounds::Fixed { rounds },
+                Rounds::Adaptive { precision, budget } => JsonRounds::Adaptive {
+                    precision: precision * 100.0,
+                    budget: budget.as_secs_f64(),
+                },
+            },
+        });
+
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| JsonEntry {
+                day: entry.puzzle,
+                part: entry.part,
+                source: &entry.source,
+                phase: &entry.phase,
+                runs: entry.samples.len(),
+                median: entry.median(),
+                mean: entry.mean(),
+                min: entry.min(),
+                max: entry.max(),
+                samples: &entry.samples,
+                allocations: entry.allocations,
+            })
+            .collect();
+
+        JsonReport {
+            metadata,
+            unit: "ms",
+            entries,
+        }
+    }
+
+    fn markdown(&self) -> String {
+        let counted = self.entries.iter().any(|entry| entry.allocations.is_some());
+
+        let mut table = String::new();
+        table.push_str("| Day | Part | Source | Phase | Median | Min | Max | Runs |");
+        if counted {
+            table.push_str(" Allocs | Allocated | Peak |");
+        }
+        table.push_str("\n|----:|-----:|--------|-------|-------:|----:|----:|-----:|");
+        if counted {
+            table.push_str("-------:|----------:|-----:|");
+        }
+        table.push('\n');
+
+        let millis = DurationFormatter::from_millis;
+        for entry in self.entries.iter() {
+            let source = entry.source.replace('|', "\\|");
+
+            // The only sample of a timeout is the limit, not a run time
+            if entry.is_timeout() {
+                write!(
+                    table,
+                    "| {} | {} | {source} | timed out | | | | |",
+                    entry.puzzle, entry.part
+                )
+                .expect("writing to a string does not fail");
+                if counted {
+                    table.push_str(" | | |");
+                }
+                table.push('\n');
+                continue;
+            }
+
+            write!(
+                table,
+                "| {} | {} | {source} | {} | {} | {} | {} | {} |",
+                entry.puzzle,
+                entry.part,
+                entry.phase,
+                millis(entry.median()),
+                millis(entry.min()),
+                millis(entry.max()),
+                entry.samples.len()
+            )
+            .expect("writing to a string does not fail");
+
+            if counted {
+                let allocations = entry.allocations.unwrap_or_default();
+                write!(
+                    table,
+                    " {} | {} | {} |",
+                    allocations.count,
+                    ByteFormatter(allocations.bytes),
+                    ByteFormatter(allocations.peak)
+                )
+                .expect("writing to a string does not fail");
+            }
+            table.push('\n');
+        }
+
+        table
+    }
+}
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    #[serde(flatten)]
+    metadata: Option<JsonMetadata<'a>>,
+    unit: &'static str,
+    entries: Vec<JsonEntry<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonMetadata<'a> {
+    // Seconds since the Unix epoch
+    timestamp: u64,
+    revision: Option<&'a str>,
+    warmup: u32,
+    rounds: JsonRounds,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "mode", rename_all = "lowercase")]
+enum JsonRounds {
+    Fixed { rounds: u32 },
+    // Precision in percent and budget in seconds, as given on the command line
+    Adaptive { precision: f64, budget: f64 },
+}
+
+#[derive(Serialize)]
+struct JsonEntry<'a> {
+    day: u32,
+    part: u32,
+    source: &'a str,
+    phase: &'a str,
+    runs: usize,
+    median: f64,
+    mean: f64,
+    min: f64,
+    max: f64,
+    samples: &'a [f64],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allocations: Option<Allocations>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    fn test_roundtrip() -> Result<(), Error> {
+        crate::util::test::setup_tracing();
+
+        let path = std::env::temp_dir().join(format!("report-{}.csv", std::process::id()));
+        let mut report = Report::default();
+        let allocations = Allocations {
+            count: 3,
+            bytes: 4096,
+            peak: 1024,
+        };
+        for (part, samples, allocations) in [
+            (1, vec![0.5, 0.25], Some(allocations)),
+            (2, vec![1.5], None),
+        ] {
+            report.entries.push(Entry {
+                puzzle: 7,
+                part,
+                source: "inputs/day07/input.txt".to_string(),
+                phase: "Solve".to_string(),
+                samples,
+                allocations,
+            });
+        }
+
+        report.save_to(&path, Format::Csv)?;
+        let loaded = Report::load_from(&path);
+        std::fs::remove_file(&path)?;
+
+        assert_eq!(loaded?.entries(), report.entries());
+        Ok(())
+    }
+
+    #[rstest]
+    fn test_formats() -> Result<(), Error> {
+        crate::util::test::setup_tracing();
+
+        let sampling = Sampling {
+            warmup: 2,
+            rounds: Rounds::Fixed(3),
+        };
+        let mut report = Report::new(Metadata::new(sampling));
+        report.push(Entry {
+            puzzle: 4,
+            part: 2,
+            source: "inputs/day04/input.txt".to_string(),
+            phase: "Solve".to_string(),
+            samples: vec![2.0, 0.5, 1.0],
+            allocations: None,
+        });
+        report.push_timeout(4, 1, "inputs/day04/input.txt", Duration::from_secs(2));
+
+        let json = serde_json::to_value(report.json())?;
+        assert_eq!(json["warmup"], 2);
+        assert_eq!(json["rounds"]["mode"], "fixed");
+        assert_eq!(json["rounds"]["rounds"], 3);
+        assert_eq!(json["entries"][0]["day"], 4);
+        assert_eq!(json["entries"][0]["median"], 1.0);
+        assert_eq!(json["entries"][0]["samples"][0], 2.0);
+
+        let markdown = report.markdown();
+        let rows = markdown.lines().collect::<Vec<_>>();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(
+            rows[2],
+            "| 4 | 2 | inputs/day04/input.txt | Solve | 1000.0μs | 500.0μs | 2.0ms | 3 |"
+        );
+        assert_eq!(
+            rows[3],
+            "| 4 | 1 | inputs/day04/input.txt | timed out | | | | |"
+        );
         Ok(())
     }
+
+    #[rstest]
+    #[case("", 1)]
+    #[case("Day 1,Part 1,input,Solve,1.0\n", 1)]
+    #[case("aoc2019 report,1\nDay 1,Part 1,input,Solve,1.0\n", 1)]
+    #[case(
+        "aoc2019 report,2\nDay 1,Part 1,input,Solve,1.0\nDay x,Part 1,input,Solve,1.0\n",
+        3
+    )]
+    #[case("aoc2019 report,2\nDay 1,Part 1,input,Solve\n", 2)]
+    #[case("aoc2019 report,2\nDay 1,Part 1,input,Solve,fast\n", 2)]
+    #[case(
+        "aoc2019 report,2\nDay 1,Part 1,input,Solve,1.0\nDay 1,Part 2,input,Solve allocations,1,2,3\n",
+        3
+    )]
+    #[case(
+        "aoc2019 report,2\nDay 1,Part 1,input,Solve,1.0\nDay 1,Part 1,input,Solve allocations,1,2\n",
+        3
+    )]
+    fn test_read_error(#[case] source: &str, #[case] expected: usize) {
+        crate::util::test::setup_tracing();
+        let reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(source.as_bytes());
+        let result = Report::read(reader);
+        let Err(Error::ReportFormat { line, .. }) = result else {
+            panic!("expected a report format error, got {result:?}");
+        };
+        assert_eq!(line, expected);
+    }
 }
@@ -0,0 +1,188 @@
+use std::str::FromStr;
+
+use crate::puzzle::TEMPLATE;
+
+// Days and parts to run, as a comma separated list of days `N`, ranges `N-M` and open ranges
+// `N-`, each optionally restricted to one part with `:1` or `:2`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selection {
+    items: Vec<Item>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Item {
+    first: u32,
+    last: Option<u32>,
+    part: Option<u32>,
+}
+
+impl Item {
+    fn contains(&self, puzzle: u32) -> bool {
+        puzzle >= self.first && self.last.is_none_or(|last| puzzle <= last)
+    }
+}
+
+impl std::fmt::Display for Item {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.last {
+            Some(last) if last == self.first => write!(f, "{}", self.first)?,
+            Some(last) => write!(f, "{}-{last}", self.first)?,
+            None => write!(f, "{}-", self.first)?,
+        }
+
+        match self.part {
+            Some(part) => write!(f, ":{part}"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Selection {
+    // Both parts of every day, which leaves out the template registered as day 0
+    pub fn all() -> Self {
+        Self {
+            items: vec![Item {
+                first: TEMPLATE + 1,
+                last: None,
+                part: None,
+            }],
+        }
+    }
+
+    pub fn parts(&self, puzzle: u32) -> [bool; 2] {
+        let mut parts = [false; 2];
+        for item in self.items.iter().filter(|item| item.contains(puzzle)) {
+            match item.part {
+                Some(part) => parts[part as usize - 1] = true,
+                None => parts = [true, true],
+            }
+        }
+        parts
+    }
+
+    // The items that select none of `puzzles`, as they were written
+    pub fn unmatched<'a>(&'a self, puzzles: &'a [u32]) -> impl Iterator<Item = String> + 'a {
+        self.items
+            .iter()
+            .filter(|item| !puzzles.iter().any(|&puzzle| item.contains(puzzle)))
+            .map(Item::to_string)
+    }
+}
+
+impl Default for Selection {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl FromStr for Selection {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let items = value
+            .split(',')
+            .map(|item| parse_item(item.trim()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { items })
+    }
+}
+
+fn parse_item(item: &str) -> Result<Item, Error> {
+    if item.is_empty() {
+        return Err(Error::Empty);
+    }
+
+    let (days, part) = match item.split_once(':') {
+        Some((days, "1")) => (days, Some(1)),
+        Some((days, "2")) => (days, Some(2)),
+        Some((_, part)) => return Err(Error::Part(part.to_string())),
+        None => (item, None),
+    };
+
+    let day = |day: &str| day.parse::<u32>().map_err(|_| Error::Day(day.to_string()));
+
+    let (first, last) = match days.split_once('-') {
+        Some((first, "")) => (day(first)?, None),
+        Some((first, last)) => (day(first)?, Some(day(last)?)),
+        None => {
+            let day = day(days)?;
+            (day, Some(day))
+        }
+    };
+
+    if let Some(last) = last.filter(|&last| last < first) {
+        return Err(Error::Range { first, last });
+    }
+
+    Ok(Item { first, last, part })
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum Error {
+    #[error("empty selector")]
+    Empty,
+    #[error("invalid day {0:?}")]
+    Day(String),
+    #[error("invalid part {0:?}, expected 1 or 2")]
+    Part(String),
+    #[error("empty range {first}-{last}")]
+    Range { first: u32, last: u32 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("7", 7, [true, true])]
+    #[case("7", 8, [false, false])]
+    #[case("7:2", 7, [false, true])]
+    #[case("1-5,9,12-", 3, [true, true])]
+    #[case("1-5,9,12-", 6, [false, false])]
+    #[case("1-5,9,12-", 25, [true, true])]
+    #[case("3:1, 1-5:2", 3, [true, true])]
+    #[case("3:1,1-5:2", 4, [false, true])]
+    #[case("0", 0, [true, true])]
+    fn test_parts(#[case] selection: &str, #[case] puzzle: u32, #[case] expected: [bool; 2]) {
+        crate::util::test::setup_tracing();
+        let selection = selection.parse::<Selection>().unwrap();
+        assert_eq!(selection.parts(puzzle), expected);
+    }
+
+    #[rstest]
+    fn test_all() {
+        crate::util::test::setup_tracing();
+        let selection = Selection::all();
+        assert_eq!(selection.parts(0), [false, false]);
+        assert_eq!(selection.parts(17), [true, true]);
+        assert_eq!(selection.unmatched(&[0, 17]).count(), 0);
+        assert_eq!(selection.unmatched(&[0]).collect::<Vec<_>>(), ["1-"]);
+    }
+
+    #[rstest]
+    #[case("1-5,9,12-,7:2", &["9", "12-"])]
+    #[case("3-3,30-40:1", &["30-40:1"])]
+    #[case("2,7:2,4-6", &[])]
+    fn test_unmatched(#[case] selection: &str, #[case] expected: &[&str]) {
+        crate::util::test::setup_tracing();
+        let selection = selection.parse::<Selection>().unwrap();
+        let puzzles = [1, 2, 3, 5, 7, 8];
+        assert_eq!(selection.unmatched(&puzzles).collect::<Vec<_>>(), expected);
+    }
+
+    #[rstest]
+    #[case("", Error::Empty)]
+    #[case("1,,2", Error::Empty)]
+    #[case("x", Error::Day("x".to_string()))]
+    #[case("-5", Error::Day("".to_string()))]
+    #[case("1-y", Error::Day("y".to_string()))]
+    #[case("7:3", Error::Part("3".to_string()))]
+    #[case("7:", Error::Part("".to_string()))]
+    #[case("5-3", Error::Range { first: 5, last: 3 })]
+    fn test_parse_error(#[case] selection: &str, #[case] expected: Error) {
+        crate::util::test::setup_tracing();
+        assert_eq!(selection.parse::<Selection>(), Err(expected));
+    }
+}
@@ -11,7 +11,7 @@ pub struct Bitmap {
 
 impl Bitmap {
     pub fn new(width: u64, height: u64) -> Self {
-        let expand = (width * height) % 64 != 0;
+        let expand = !(width * height).is_multiple_of(64);
         let len = (width * height) / 64 + (if expand { 1 } else { 0 });
         let contents = vec![0; len as usize];
 
@@ -1,6 +1,3 @@
-#![allow(dead_code)]
-#![allow(unused_imports)]
-
 pub mod bitmap;
 pub mod cache;
 pub mod display;
@@ -12,6 +9,7 @@ pub mod position;
 pub mod range;
 pub mod sequence;
 pub mod slice;
+#[cfg(any(test, feature = "test-util"))]
 pub mod test;
 pub mod tree;
 pub mod vector;
@@ -62,15 +62,6 @@ impl<N> MultiRangeInternal<N>
 where
     N: Eq + Ord + Clone,
 {
-    #[allow(dead_code)]
-    pub fn len(&self) -> usize {
-        match self {
-            Self::Empty => 0,
-            Self::Single(..) => 1,
-            Self::Multiple(vec) => vec.len(),
-        }
-    }
-
     pub fn is_empty(&self) -> bool {
         match self {
             Self::Empty => true,
@@ -0,0 +1,222 @@
+use std::{collections::BTreeMap, path::Path};
+
+use crate::{Error, Result};
+
+pub const ANSWERS_FILE: &str = "inputs/answers.txt";
+
+// Expected answers, one section per day and part. A section starts with a `day N part M`
+// header and its answer spans every line up to the next header, so bitmap answers can be
+// stored as they are printed. Lines starting with `#` are comments.
+#[derive(Debug, Default)]
+pub struct Answers {
+    answers: BTreeMap<(u32, u32), String>,
+}
+
+impl Answers {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path).map_err(|source| Error::AnswersFile {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&source)
+    }
+
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut answers = BTreeMap::new();
+        let mut current: Option<((u32, u32), usize, Vec<&str>)> = None;
+
+        let mut finish = |section: Option<((u32, u32), usize, Vec<&str>)>| {
+            let Some((key, line, lines)) = section else {
+                return Ok(());
+            };
+
+            let answer = normalize(&lines.join("\n"));
+            if answer.is_empty() {
+                return Err(Error::answers(line, "missing answer"));
+            }
+
+            match answers.insert(key, answer) {
+                Some(_) => Err(Error::answers(line, "duplicate answer")),
+                None => Ok(()),
+            }
+        };
+
+        for (line_idx, line) in source.lines().enumerate() {
+            let line_number = line_idx + 1;
+            if line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(key) = parse_header(line, line_number)? {
+                finish(current.take())?;
+                current = Some((key, line_number, Vec::new()));
+            } else if let Some((_, _, lines)) = current.as_mut() {
+                lines.push(line);
+            } else if !line.trim().is_empty() {
+                return Err(Error::answers(line_number, "answer outside of a section"));
+            }
+        }
+
+        finish(current)?;
+
+        Ok(Self { answers })
+    }
+
+    pub fn check(&self, puzzle: u32, part: u32, result: &str) -> Verdict {
+        match self.answers.get(&(puzzle, part)) {
+            Some(expected) if *expected == normalize(result) => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.clone(),
+            },
+            None => Verdict::Missing,
+        }
+    }
+}
+
+fn parse_header(line: &str, line_number: usize) -> Result<Option<(u32, u32)>> {
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    let ["day", puzzle, "part", part] = words.as_slice() else {
+        return Ok(None);
+    };
+
+    let puzzle = puzzle
+        .parse()
+        .map_err(|_| Error::answers(line_number, format!("invalid day {puzzle:?}")))?;
+    let part = match *part {
+        "1" => 1,
+        "2" => 2,
+        _ => {
+            return Err(Error::answers(
+                line_number,
+                format!("invalid part {part:?}"),
+            ))
+        }
+    };
+
+    Ok(Some((puzzle, part)))
+}
+
+// Answers are compared without surrounding blank lines or trailing whitespace, which editors
+// tend to strip
+fn normalize(answer: &str) -> String {
+    let lines = answer.lines().map(str::trim_end).collect::<Vec<_>>();
+    let start = lines.iter().position(|line| !line.is_empty());
+    let end = lines.iter().rposition(|line| !line.is_empty());
+
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end].join("\n"),
+        _ => String::new(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+impl Verdict {
+    pub fn describe(&self, result: &str) -> String {
+        match self {
+            Self::Pass => "pass".to_string(),
+            Self::Fail { expected } if expected.contains('\n') => {
+                format!("FAIL, expected\n{expected}\ngot {result}")
+            }
+            Self::Fail { expected } => format!("FAIL, expected {expected}, got {result}"),
+            Self::Missing => format!("missing, got {result}"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Summary {
+    pub passed: usize,
+    pub failed: usize,
+    pub missing: usize,
+}
+
+impl Summary {
+    pub fn record(&mut self, verdict: &Verdict) {
+        match verdict {
+            Verdict::Pass => self.passed += 1,
+            Verdict::Fail { .. } => self.failed += 1,
+            Verdict::Missing => self.missing += 1,
+        }
+    }
+
+    pub fn record_error(&mut self) {
+        self.failed += 1;
+    }
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} passed, {} failed, {} missing",
+            self.passed, self.failed, self.missing
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    const SOURCE: &str = "\
+# Expected answers
+day 1 part 1
+3239890
+
+day 8 part 2
+ ██  █
+█  █ █
+
+day 11 part 2
+  ██
+";
+
+    #[rstest]
+    #[case(1, 1, "3239890", Verdict::Pass)]
+    #[case(1, 1, "3239891", Verdict::Fail { expected: "3239890".to_string() })]
+    #[case(1, 2, "4856963", Verdict::Missing)]
+    #[case(8, 2, "\n ██  █\n█  █ █", Verdict::Pass)]
+    #[case(11, 2, "\n  ██", Verdict::Pass)]
+    fn test_check(
+        #[case] puzzle: u32,
+        #[case] part: u32,
+        #[case] result: &str,
+        #[case] expected: Verdict,
+    ) -> Result<()> {
+        crate::util::test::setup_tracing();
+        let answers = Answers::parse(SOURCE)?;
+        assert_eq!(answers.check(puzzle, part, result), expected);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("day 1 part 3\n1", 1)]
+    #[case("day x part 1\n1", 1)]
+    #[case("day 1 part 1\n\nday 1 part 2\n2", 1)]
+    #[case("day 1 part 1\n1\nday 1 part 1\n2", 3)]
+    #[case("1\nday 1 part 1\n1", 1)]
+    fn test_parse_error(#[case] source: &str, #[case] expected: usize) {
+        crate::util::test::setup_tracing();
+        let result = Answers::parse(source);
+        let Err(Error::Answers { line, .. }) = result else {
+            panic!("expected an answers error, got {result:?}");
+        };
+        assert_eq!(line, expected);
+    }
+
+    #[rstest]
+    fn test_load_missing() {
+        crate::util::test::setup_tracing();
+        let path = std::env::temp_dir().join(format!("answers-{}.txt", std::process::id()));
+        let message = Answers::load(&path).unwrap_err().to_string();
+        assert!(message.contains(&path.display().to_string()), "{message}");
+    }
+}